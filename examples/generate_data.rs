@@ -1,224 +1,211 @@
 //! Data set generator for the examples.
 
+use std::env;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::LazyLock;
+use std::path::{Path, PathBuf};
 
 use poetry_contest::find_workspace_root;
+use poetry_contest::generator::{Generator, Sizes};
 use rand::Rng;
-use serde::Serialize;
 
 fn main() {
-    // Replaces contents of `data/` in the workspace root with a fresh data set, deleting existing
-    // contents of `data/` first and creating the directory if it does not already exist.
+    // Replaces contents of the output directory with a fresh data set, deleting existing
+    // contents first and creating the directory if it does not already exist.
+    //
+    // The output directory is `data/` in the workspace root by default. Pass `--name <name>` to
+    // generate a named data set in `data-<name>/` instead, so multiple data sets can be kept side
+    // by side, or `--out <dir>` to generate the data set in an arbitrary directory.
     //
     // The workspace root is defined as the directory containing `Cargo.toml`, searching upwards
     // from the current directory.
     //
-    // The manifest is serialized as `manifest.json` in the `data/` directory, and each round
+    // To avoid accidents, a non-empty output directory is only deleted if it contains a manifest,
+    // i.e. looks like a previously generated data set. Pass `--force` to delete it regardless.
+    //
+    // The manifest is serialized as `manifest.json` in the output directory, and each round
     // is created as a separate JSON file in the same directory, named `round_<index>.json`.
-
-    let workspace_root = find_workspace_root();
-    let data_dir = workspace_root.join("data");
+    // The expected result is calculated while generating and saved as `expected.json` next to
+    // the manifest, so the solvers can be verified against it (see `generator::Expected`).
+    //
+    // Pass `--seed <number>` to generate a specific data set. The same seed always yields
+    // byte-identical files. Without a seed, a random one is chosen and printed, so any data set
+    // can be reproduced later.
+    //
+    // The size of the data set is determined by `Sizes`. Pass `--preset <file>` to load sizes
+    // from a JSON file (see `examples/presets/`) and/or `--<size> <number>` to override individual
+    // sizes, e.g. `--round-count 10`. Unspecified sizes keep their default values. The same
+    // applies to `edge_case_percent`, which makes some entries adversarial, to
+    // `phrase_keyword_percent`, which makes some keywords phrases of two words, and to
+    // `named_category_percent`, which gives some categories an id, a name and a description.
+
+    let Args {
+        seed,
+        sizes,
+        data_dir,
+        force,
+    } = parse_args();
 
     // Delete and recreate the data directory
     if data_dir.exists() {
+        assert!(
+            force || is_safe_to_delete(&data_dir),
+            "Refusing to delete {} because it is not empty and does not contain a manifest. \
+             Use --force to delete it anyway.",
+            data_dir.display()
+        );
+
         println!("Removing existing data directory: {}", data_dir.display());
         fs::remove_dir_all(&data_dir).expect("Failed to remove existing data directory");
     }
     fs::create_dir_all(&data_dir).expect("Failed to create data directory");
 
     println!("Generating data set in: {}", data_dir.display());
+    println!("Seed: {seed}");
+    println!("Sizes: {sizes:?}");
 
-    // Generate categories
-    let mut categories = Vec::with_capacity(CATEGORY_COUNT);
-    for _ in 0..CATEGORY_COUNT {
-        categories.push(generate_category());
-    }
-    println!("Generated {} categories", categories.len());
+    let mut generator = Generator::new(seed, sizes);
+    println!("Generated {} categories", generator.sizes().category_count);
 
     // Generate rounds with entries and write each round to a separate file
-    let mut round_paths = Vec::with_capacity(ROUND_COUNT);
-    let mut entry_index = 0;
-    for round_idx in 0..ROUND_COUNT {
-        let round = generate_round();
-        entry_index += round.entries.len();
-        
-        // Write round to its own JSON file
-        let round_filename = format!("round_{}.json", round_idx);
-        let round_path = data_dir.join(&round_filename);
-        let round_json = serde_json::to_string_pretty(&round).expect("Failed to serialize round");
-        fs::write(&round_path, round_json).expect(&format!(
-            "Failed to write round file: {}",
-            round_path.display()
-        ));
-        
-        // Store relative path in manifest
-        round_paths.push(PathBuf::from(round_filename));
-        
-        if (round_idx + 1) % 10 == 0 || round_idx == ROUND_COUNT - 1 {
+    while let Some(round) = generator.next_round() {
+        let round_path = data_dir.join(&round.path);
+        fs::write(&round_path, round.json)
+            .unwrap_or_else(|e| panic!("Failed to write round file {}: {e}", round_path.display()));
+
+        let round_count = generator.round_count();
+
+        if round_count.is_multiple_of(10) || round_count == generator.sizes().round_count {
             println!(
                 "Generated {}/{} rounds ({} total entries)",
-                round_idx + 1,
-                ROUND_COUNT,
-                entry_index
+                round_count,
+                generator.sizes().round_count,
+                generator.entry_count()
             );
         }
     }
 
-    // Create manifest
-    let manifest = Manifest { categories, rounds: round_paths };
-
-    // Serialize manifest to JSON
     let manifest_path = data_dir.join("manifest.json");
-    let manifest_json =
-        serde_json::to_string_pretty(&manifest).expect("Failed to serialize manifest");
-    fs::write(&manifest_path, manifest_json).expect("Failed to write manifest file");
+    fs::write(&manifest_path, generator.manifest_json()).expect("Failed to write manifest file");
+
+    let expected = generator.expected();
+    let expected_json =
+        serde_json::to_string_pretty(expected).expect("Failed to serialize expected result");
+    fs::write(data_dir.join("expected.json"), expected_json)
+        .expect("Failed to write expected result file");
 
     println!("Data generation complete!");
     println!("Manifest: {}", manifest_path.display());
-    println!("Total entries: {}", entry_index);
-}
-
-const ROUND_COUNT: usize = 500;
-const CATEGORY_COUNT: usize = 50;
-const AUTHOR_COUNT: usize = 250;
-const MIN_ENTRIES_PER_ROUND: usize = 500;
-const MAX_ENTRIES_PER_ROUND: usize = 2000;
-const MIN_ENTRY_WORDS: usize = 50;
-const MAX_ENTRY_WORDS: usize = 500;
-const MIN_CATEGORY_KEYWORDS: usize = 1;
-const MAX_CATEGORY_KEYWORDS: usize = 500;
-const LINE_LENGTH_WORDS: usize = 32;
-
-/// How many words there are in the vocabulary we use.
-///
-/// For simplify, we do not use real words, we just use integers as words. The first word is "1",
-/// then "2", and so on up to `VOCABULARY_SIZE`.
-const VOCABULARY_SIZE: usize = 128_000;
-
-/// The manifest is the root object of the data set.
-///
-/// It defines all the metadata and references all the other files that make up the data set.
-///
-/// This is the manifest of one poetry contest, whereby entries from different rounds are evaluated
-/// against different categories, with the authors gaining points based on the scores they receive
-/// in each round of the contest.
-#[derive(Serialize)]
-struct Manifest {
-    categories: Vec<Category>,
-    /// Relative paths from the directory of the manifest file to the round JSON files.
-    rounds: Vec<PathBuf>,
-}
-
-/// One category that entries are evaluated against.
-///
-/// A category is simply a set of keywords that are used to identify entries
-/// that belong in that category.
-#[derive(Serialize)]
-struct Category {
-    /// Keywords that define the category.
-    ///
-    /// Between `MIN_CATEGORY_KEYWORDS` and `MAX_CATEGORY_KEYWORDS` keywords in each category,
-    /// randomly chosen from the vocabulary.
-    keywords: Vec<&'static str>,
+    println!("Total entries: {}", generator.entry_count());
+    println!("Expected total score: {}", expected.total);
 }
 
-/// One round of the contest.
-#[derive(Serialize)]
-struct Round {
-    /// All the entries that compete in the round.
-    ///
-    /// Between `MIN_ENTRIES_PER_ROUND` and `MAX_ENTRIES_PER_ROUND` entries in each round, random.
-    entries: Vec<Entry>,
-}
-
-#[derive(Serialize)]
-struct Entry {
-    /// Name of the author - the person that any scoring is attributed to.
-    author: String,
-
-    /// The title of the entry, used for category matching.
-    title: String,
-
-    /// The actual text content of the entry.
-    contents: String,
-}
-
-static VOCABULARY: LazyLock<Vec<String>> = LazyLock::new(|| {
-    let mut words = Vec::with_capacity(VOCABULARY_SIZE);
-
-    for i in 1..=VOCABULARY_SIZE {
-        words.push(i.to_string());
+/// Whether the directory is either empty or looks like a previously generated data set.
+fn is_safe_to_delete(dir: &Path) -> bool {
+    if dir.join("manifest.json").is_file() {
+        return true;
     }
 
-    words
-});
-
-fn word() -> &'static str {
-    /// There is more diversity in word length at the low end of the range (99 and 100 differ more
-    /// significantly than 2099 and 2100), so we bias the distribution toward the low end.
-    const LOW_BIAS_POWER: usize = 3;
-
-    let selector = rand::rng().random::<f64>().powf(LOW_BIAS_POWER as f64);
-    let word_index = (selector * (VOCABULARY_SIZE as f64)) as usize + 1;
-    VOCABULARY[word_index - 1].as_str()
+    fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Failed to read directory {}: {e}", dir.display()))
+        .next()
+        .is_none()
 }
 
-fn generate_category() -> Category {
-    let keyword_count = rand::rng().random_range(MIN_CATEGORY_KEYWORDS..=MAX_CATEGORY_KEYWORDS);
-    let keywords = (0..keyword_count).map(|_| word()).collect();
-    Category { keywords }
+struct Args {
+    seed: u64,
+    sizes: Sizes,
+    data_dir: PathBuf,
+    force: bool,
 }
 
-fn generate_round() -> Round {
-    let entry_count = rand::rng().random_range(MIN_ENTRIES_PER_ROUND..=MAX_ENTRIES_PER_ROUND);
-    let mut entries = Vec::with_capacity(entry_count);
-
-    for _ in 0..entry_count {
-        let entry = generate_entry();
-        entries.push(entry);
+/// Parses the command line. Panics with a descriptive message if it is not valid.
+fn parse_args() -> Args {
+    let mut seed = None;
+    let mut preset = None;
+    let mut data_dir = None;
+    let mut force = false;
+    let mut overrides = Vec::new();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("{arg} requires a value"))
+        };
+
+        match arg.as_str() {
+            "--seed" => {
+                let value = value();
+                seed = Some(
+                    value
+                        .parse()
+                        .unwrap_or_else(|e| panic!("Invalid seed '{value}': {e}")),
+                );
+            }
+            "--preset" => preset = Some(PathBuf::from(value())),
+            "--out" | "--name" => {
+                let dir = match arg.as_str() {
+                    "--out" => PathBuf::from(value()),
+                    _ => find_workspace_root().join(format!("data-{}", value())),
+                };
+
+                assert!(
+                    data_dir.replace(dir).is_none(),
+                    "Only one of --out and --name can be given, once"
+                );
+            }
+            "--force" => force = true,
+            size if size.starts_with("--") => {
+                let value = value();
+                let value: usize = value
+                    .parse()
+                    .unwrap_or_else(|e| panic!("Invalid value '{value}' for {size}: {e}"));
+                overrides.push((size[2..].replace('-', "_"), value));
+            }
+            other => panic!("Unknown argument '{other}'"),
+        }
     }
 
-    Round { entries }
-}
+    let mut sizes = preset.map_or_else(Sizes::default, |path| load_preset(&path));
 
-fn generate_entry() -> Entry {
-    let mut rng = rand::rng();
-
-    // Generate author (random from 0 to AUTHOR_COUNT-1)
-    let author_index = rng.random_range(0..AUTHOR_COUNT).to_string();
+    for (name, value) in overrides {
+        set_size(&mut sizes, &name, value);
+    }
 
-    // Generate title (first LINE_LENGTH_WORDS words)
-    let mut title = String::with_capacity(128);
-    for i in 0..LINE_LENGTH_WORDS {
-        if i > 0 {
-            title.push(' ');
-        }
-        title.push_str(word());
+    Args {
+        seed: seed.unwrap_or_else(|| rand::rng().random()),
+        sizes,
+        data_dir: data_dir.unwrap_or_else(|| find_workspace_root().join("data")),
+        force,
     }
+}
 
-    // Generate entry content (remaining words)
-    let word_count = rng.random_range(MIN_ENTRY_WORDS..=MAX_ENTRY_WORDS);
-    let content_word_count = word_count.saturating_sub(LINE_LENGTH_WORDS);
-
-    let mut contents = String::with_capacity(1024);
-    for i in 0..content_word_count {
-        if i > 0 {
-            // Check if we should insert a line break
-            if i % LINE_LENGTH_WORDS == 0 {
-                contents.push('\n');
-            } else {
-                contents.push(' ');
-            }
-        }
+/// Loads sizes from a JSON preset file. Sizes missing from the file keep their defaults.
+fn load_preset(path: &Path) -> Sizes {
+    let json = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Failed to read preset {}: {e}", path.display()));
 
-        contents.push_str(word());
-    }
+    serde_json::from_str(&json).unwrap_or_else(|e| panic!("Invalid preset {}: {e}", path.display()))
+}
 
-    Entry {
-        author: author_index,
-        title,
-        contents,
-    }
+fn set_size(sizes: &mut Sizes, name: &str, value: usize) {
+    let size = match name {
+        "round_count" => &mut sizes.round_count,
+        "category_count" => &mut sizes.category_count,
+        "author_count" => &mut sizes.author_count,
+        "min_entries_per_round" => &mut sizes.min_entries_per_round,
+        "max_entries_per_round" => &mut sizes.max_entries_per_round,
+        "min_entry_words" => &mut sizes.min_entry_words,
+        "max_entry_words" => &mut sizes.max_entry_words,
+        "min_category_keywords" => &mut sizes.min_category_keywords,
+        "max_category_keywords" => &mut sizes.max_category_keywords,
+        "line_length_words" => &mut sizes.line_length_words,
+        "vocabulary_size" => &mut sizes.vocabulary_size,
+        "edge_case_percent" => &mut sizes.edge_case_percent,
+        "phrase_keyword_percent" => &mut sizes.phrase_keyword_percent,
+        "named_category_percent" => &mut sizes.named_category_percent,
+        _ => panic!("Unknown argument '--{}'", name.replace('_', "-")),
+    };
+
+    *size = value;
 }
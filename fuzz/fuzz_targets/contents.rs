@@ -0,0 +1,72 @@
+//! Feeds arbitrary raw contents to the contents decoders of the solvers that have one and checks
+//! that they agree with serde_json.
+//!
+//! Run with `cargo +nightly fuzz run contents` from the workspace root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use poetry_contest::{
+    DisqualificationReason, Rules, Weight, WordBoundary, fuzzing::CONTENTS_DECODERS,
+};
+use serde_json::value::RawValue;
+
+fuzz_target!(|input: &str| {
+    // The input as any JSON value, as well as the input as the inside of a JSON string, which
+    // is where all the interesting decoding happens.
+    for raw in [input.to_owned(), format!("\"{input}\"")] {
+        // The solvers only ever see contents that serde_json accepted as a JSON value.
+        let Ok(raw) = RawValue::from_string(raw) else {
+            continue;
+        };
+
+        check(&raw);
+    }
+});
+
+fn check(raw: &RawValue) {
+    let is_string = raw.get().starts_with('"');
+    let reference = serde_json::from_str::<String>(raw.get());
+
+    for decoder in &CONTENTS_DECODERS {
+        let solver = decoder.solver;
+        let length = (decoder.calculate_json_string_length)(raw);
+
+        for boundary in WordBoundary::ALL {
+            let weight = decoder.calculate_weight(raw, boundary, Weight::WordDensity);
+
+            if !is_string {
+                assert_eq!(length, Err(DisqualificationReason::NotAString), "{solver}");
+                assert_eq!(weight, Err(DisqualificationReason::NotAString), "{solver}");
+                continue;
+            }
+
+            let Ok(text) = &reference else {
+                // serde_json cannot decode it, so it must be disqualified, although a long text
+                // may be disqualified for its length before the decoder even reaches the problem.
+                assert!(
+                    matches!(
+                        weight,
+                        Err(DisqualificationReason::InvalidEscape
+                            | DisqualificationReason::InvalidUtf8
+                            | DisqualificationReason::TooLong)
+                    ),
+                    "{solver}: {weight:?}"
+                );
+                continue;
+            };
+
+            assert_eq!(length, Ok(text.len()), "{solver}");
+
+            let expected_weight = if text.len() > Rules::DEFAULT_MAX_BYTES {
+                Err(DisqualificationReason::TooLong)
+            } else if !boundary.has_words(text) {
+                Err(DisqualificationReason::Empty)
+            } else {
+                Ok(text.len() as f64 / boundary.word_count(text) as f64)
+            };
+
+            assert_eq!(weight, expected_weight, "{solver} {boundary:?}");
+        }
+    }
+}
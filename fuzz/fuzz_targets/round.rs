@@ -0,0 +1,76 @@
+//! Feeds arbitrary round JSON to every solver and checks that they agree with v01, which leaves
+//! all decoding to serde_json.
+//!
+//! Run with `cargo +nightly fuzz run round` from the workspace root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use poetry_contest::{SolveOptions, WordBoundary, solvers};
+
+/// Short keywords and a short phrase, so that the fuzzer easily finds titles that match.
+const MANIFEST_JSON: &str = r#"{
+    "categories": [
+        { "keywords": ["a"] },
+        { "keywords": ["b", "c"] },
+        { "keywords": ["a", "1"] },
+        { "keywords": ["c a"] }
+    ],
+    "rounds": ["round.json"]
+}"#;
+
+/// Solvers that let serde_json decode the contents, rejecting the entire round if any contents
+/// cannot be decoded. Later solvers disqualify just the entry instead.
+const SERDE_DECODING_SOLVERS: usize = 5;
+
+fuzz_target!(|round_json: &str| {
+    let options = SolveOptions {
+        round_details: true,
+        disqualifications: true,
+        // Derived from the input so that every policy is covered without tripling the work.
+        word_boundary: WordBoundary::ALL[round_json.len() % WordBoundary::ALL.len()],
+        ..Default::default()
+    };
+
+    let results = solvers()
+        .iter()
+        .map(|solver| {
+            let result = solver.solve_str_with(
+                MANIFEST_JSON,
+                &mut |_, buffer| {
+                    buffer.push_str(round_json);
+                    Ok(())
+                },
+                &options,
+            );
+
+            (solver.name(), result)
+        })
+        .collect::<Vec<_>>();
+
+    let (reference_name, reference) = &results[0];
+
+    for (index, (name, result)) in results.iter().enumerate().skip(1) {
+        match (reference, result) {
+            (Ok(reference), Ok(result)) => {
+                assert_eq!(
+                    reference.scores(),
+                    result.scores(),
+                    "{name} scores differ from {reference_name}"
+                );
+                assert_eq!(
+                    reference.disqualifications(),
+                    result.disqualifications(),
+                    "{name} disqualifications differ from {reference_name}"
+                );
+            }
+            (Err(_), Err(_)) => {}
+            (Err(_), Ok(_)) if index >= SERDE_DECODING_SOLVERS => {}
+            (reference, result) => panic!(
+                "{name} returned {:?} but {reference_name} returned {:?}",
+                result.as_ref().map(|r| r.total()),
+                reference.as_ref().map(|r| r.total()),
+            ),
+        }
+    }
+});
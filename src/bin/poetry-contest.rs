@@ -0,0 +1,413 @@
+//! Command-line interface for solving a poetry contest with any version of the solver.
+
+use std::{
+    env,
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use poetry_contest::{
+    CategoryInfo, ContestError, ContestResult, Disqualification, KeywordMatching, LeaderboardEntry,
+    NormalizationForm, Punctuation, RoundDetails, Score, SolveOptions, Solver, WordBoundary,
+    find_solver, solvers,
+};
+use serde::Serialize;
+
+const USAGE: &str = "\
+Usage: poetry-contest [OPTIONS] <MANIFEST>
+
+Solves the poetry contest described by the manifest and prints the result.
+Round files are resolved relative to the directory that contains the manifest.
+
+Options:
+  --solver <NAME>       Solver to use, by version (v01) or full name (v01_simple) [default: v08]
+  --output <MODE>       What to print: total, leaderboard or json [default: total]
+  --details             Include the winners of every category of every round in the leaderboard
+                        and JSON report
+  --disqualifications   Include every disqualified entry in the JSON report
+  --word-boundary <POLICY>
+                        How to split titles and contents into words: unicode-whitespace,
+                        ascii-whitespace or unicode-words [default: unicode-whitespace]
+  --trim-punctuation    Trim punctuation from both ends of title words and keywords before
+                        matching
+  --punctuation <CHARS> Trim these characters instead of the default punctuation
+  --case-folding        Match title words against keywords regardless of case
+  --normalization <FORM>
+                        Normalize title words and keywords before matching: nfc or nfkc
+  --list-solvers        Print the available solvers and exit
+  -h, --help            Print this help and exit
+
+Exit codes:
+  0   Success
+  64  Invalid command line
+  65  The manifest or a round file is malformed, or points or a score overflow
+  66  The manifest could not be read
+  74  A round file could not be read";
+
+// Exit codes follow the BSD sysexits.h conventions.
+const EXIT_USAGE: u8 = 64;
+const EXIT_DATA_ERROR: u8 = 65;
+const EXIT_NO_INPUT: u8 = 66;
+const EXIT_IO_ERROR: u8 = 74;
+
+fn main() -> ExitCode {
+    let command = match parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let args = match command {
+        Command::Help => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Command::ListSolvers => {
+            for solver in solvers() {
+                println!("{:<24}{}", solver.name(), solver.description());
+            }
+            return ExitCode::SUCCESS;
+        }
+        Command::Solve(args) => args,
+    };
+
+    let options = SolveOptions {
+        round_details: args.details,
+        disqualifications: args.disqualifications,
+        word_boundary: args.word_boundary,
+        keyword_matching: args.keyword_matching,
+        ..Default::default()
+    };
+
+    let result = match args.solver.solve_manifest_with(&args.manifest, &options) {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return ExitCode::from(exit_code(&error));
+        }
+    };
+
+    let mut stdout = io::stdout().lock();
+
+    let written = match args.output {
+        Output::Total => writeln!(stdout, "{}", result.total()),
+        Output::Leaderboard => write_leaderboard(&mut stdout, &result),
+        Output::Json => write_json(&mut stdout, args.solver, &result),
+    };
+
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        // Someone piped us into `head` or similar and stopped reading, which is fine.
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: failed to write output: {error}");
+            ExitCode::from(EXIT_IO_ERROR)
+        }
+    }
+}
+
+fn exit_code(error: &ContestError) -> u8 {
+    match error {
+        ContestError::MissingManifest { .. } => EXIT_NO_INPUT,
+        ContestError::UnreadableRound { .. } => EXIT_IO_ERROR,
+        ContestError::MalformedManifest { .. } | ContestError::MalformedRound { .. } => {
+            EXIT_DATA_ERROR
+        }
+        // The data is valid, but awards more points than a place or a score can hold.
+        ContestError::PointsOverflow { .. } | ContestError::ScoreOverflow { .. } => EXIT_DATA_ERROR,
+    }
+}
+
+fn write_leaderboard(out: &mut impl Write, result: &ContestResult) -> io::Result<()> {
+    writeln!(out, "{:>6} {:>8}  author", "rank", "score")?;
+
+    for entry in result.leaderboard() {
+        writeln!(
+            out,
+            "{:>6} {:>8}  {}",
+            entry.rank, entry.score, entry.author
+        )?;
+    }
+
+    writeln!(out, "{:>6} {:>8}", "total", result.total())?;
+
+    let (Some(categories), Some(rounds)) = (result.categories(), result.rounds()) else {
+        return Ok(());
+    };
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{:>6} {:>6} {:>8}  category: authors",
+        "round", "place", "points"
+    )?;
+
+    for round in rounds {
+        for winners in &round.categories {
+            writeln!(
+                out,
+                "{:>6} {:>6} {:>8}  {}: {}",
+                round.index,
+                winners.place,
+                winners.points,
+                categories[winners.category].label(),
+                winners.authors.join(", ")
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The JSON report printed by `--output json`.
+#[derive(Serialize)]
+struct Report<'a> {
+    solver: &'static str,
+    total: Score,
+    leaderboard: Vec<LeaderboardEntry<'a>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    categories: Option<&'a [CategoryInfo]>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rounds: Option<&'a [RoundDetails]>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disqualifications: Option<&'a [Disqualification]>,
+}
+
+fn write_json(out: &mut impl Write, solver: &dyn Solver, result: &ContestResult) -> io::Result<()> {
+    let report = Report {
+        solver: solver.name(),
+        total: result.total(),
+        leaderboard: result.leaderboard(),
+        categories: result.categories(),
+        rounds: result.rounds(),
+        disqualifications: result.disqualifications(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
+
+enum Command {
+    Help,
+    ListSolvers,
+    Solve(Args),
+}
+
+struct Args {
+    manifest: PathBuf,
+    solver: &'static dyn Solver,
+    output: Output,
+    details: bool,
+    disqualifications: bool,
+    word_boundary: WordBoundary,
+    keyword_matching: KeywordMatching,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Output {
+    Total,
+    Leaderboard,
+    Json,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut manifest = None;
+    let mut solver = None;
+    let mut output = Output::Total;
+    let mut details = false;
+    let mut disqualifications = false;
+    let mut word_boundary = WordBoundary::default();
+    let mut keyword_matching = KeywordMatching::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--list-solvers" => return Ok(Command::ListSolvers),
+            "--solver" => {
+                let name = args.next().ok_or("--solver requires a value")?;
+                solver = Some(find_solver(&name).ok_or(format!("unknown solver '{name}'"))?);
+            }
+            "--output" => {
+                output = match args.next().ok_or("--output requires a value")?.as_str() {
+                    "total" => Output::Total,
+                    "leaderboard" => Output::Leaderboard,
+                    "json" => Output::Json,
+                    other => return Err(format!("unknown output mode '{other}'")),
+                };
+            }
+            "--details" => details = true,
+            "--disqualifications" => disqualifications = true,
+            "--word-boundary" => {
+                let name = args.next().ok_or("--word-boundary requires a value")?;
+                word_boundary = WordBoundary::from_name(&name)
+                    .ok_or(format!("unknown word boundary '{name}'"))?;
+            }
+            "--trim-punctuation" => {
+                keyword_matching
+                    .trim_punctuation
+                    .get_or_insert_with(Punctuation::default);
+            }
+            "--punctuation" => {
+                let chars = args.next().ok_or("--punctuation requires a value")?;
+                keyword_matching.trim_punctuation = Some(Punctuation::new(chars));
+            }
+            "--case-folding" => keyword_matching.case_folding = true,
+            "--normalization" => {
+                let name = args.next().ok_or("--normalization requires a value")?;
+                keyword_matching.normalization = Some(
+                    NormalizationForm::from_name(&name)
+                        .ok_or(format!("unknown normalization form '{name}'"))?,
+                );
+            }
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{flag}'")),
+            path => {
+                if manifest.replace(PathBuf::from(path)).is_some() {
+                    return Err("only one manifest can be solved at a time".to_owned());
+                }
+            }
+        }
+    }
+
+    Ok(Command::Solve(Args {
+        manifest: manifest.ok_or("the path to the manifest is required")?,
+        solver: solver.unwrap_or_else(|| *solvers().last().expect("there is always a solver")),
+        output,
+        details,
+        disqualifications,
+        word_boundary,
+        keyword_matching,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| (*arg).to_owned()))
+    }
+
+    #[test]
+    fn defaults() {
+        let Ok(Command::Solve(args)) = parse(&["data/manifest.json"]) else {
+            panic!("expected a solve command");
+        };
+
+        assert_eq!(args.manifest, PathBuf::from("data/manifest.json"));
+        assert_eq!(args.solver.name(), "v08_faster_maps");
+        assert_eq!(args.output, Output::Total);
+        assert!(!args.details);
+        assert!(!args.disqualifications);
+        assert_eq!(args.word_boundary, WordBoundary::UnicodeWhitespace);
+        assert_eq!(args.keyword_matching, KeywordMatching::default());
+    }
+
+    #[test]
+    fn all_options() {
+        let Ok(Command::Solve(args)) = parse(&[
+            "--solver",
+            "v02",
+            "--output",
+            "json",
+            "--details",
+            "contest.json",
+            "--disqualifications",
+            "--word-boundary",
+            "unicode-words",
+            "--case-folding",
+            "--normalization",
+            "nfkc",
+            "--punctuation",
+            "!?",
+        ]) else {
+            panic!("expected a solve command");
+        };
+
+        assert_eq!(args.manifest, PathBuf::from("contest.json"));
+        assert_eq!(args.solver.name(), "v02_less_cloning");
+        assert_eq!(args.output, Output::Json);
+        assert!(args.details);
+        assert!(args.disqualifications);
+        assert_eq!(args.word_boundary, WordBoundary::UnicodeWords);
+        assert_eq!(
+            args.keyword_matching,
+            KeywordMatching {
+                case_folding: true,
+                normalization: Some(NormalizationForm::Nfkc),
+                trim_punctuation: Some(Punctuation::new("!?")),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["a.json", "b.json"]).is_err());
+        assert!(parse(&["--solver", "v42", "a.json"]).is_err());
+        assert!(parse(&["--output", "xml", "a.json"]).is_err());
+        assert!(parse(&["--word-boundary", "emoji", "a.json"]).is_err());
+        assert!(parse(&["--normalization", "nfd", "a.json"]).is_err());
+        assert!(parse(&["a.json", "--punctuation"]).is_err());
+        assert!(parse(&["--solver"]).is_err());
+        assert!(parse(&["--frobnicate", "a.json"]).is_err());
+    }
+
+    #[test]
+    fn leaderboard_lists_winners_by_category_label() {
+        let manifest_json = r#"{
+            "categories": [
+                { "keywords": ["rose"], "name": "Roses" },
+                { "keywords": ["blue"], "id": "blue" }
+            ],
+            "rounds": ["round_0.json"]
+        }"#;
+
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "rose", "contents": "aaa" },
+                { "author": "B", "title": "rose blue", "contents": "bbb" }
+            ]
+        }"#;
+
+        let options = SolveOptions {
+            round_details: true,
+            ..Default::default()
+        };
+
+        let result = solvers()[0]
+            .solve_str_with(
+                manifest_json,
+                &mut |_, buffer| {
+                    buffer.push_str(round_json);
+                    Ok(())
+                },
+                &options,
+            )
+            .unwrap();
+
+        let mut out = Vec::new();
+        write_leaderboard(&mut out, &result).unwrap();
+
+        let lines = [
+            "  rank    score  author",
+            "     1        2  B",
+            "     2        1  A",
+            " total        3",
+            "",
+            " round  place   points  category: authors",
+            "     0      1        1  Roses: A, B",
+            "     0      1        1  blue: B",
+        ];
+
+        assert_eq!(
+            String::from_utf8(out).unwrap().lines().collect::<Vec<_>>(),
+            lines
+        );
+    }
+}
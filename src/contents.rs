@@ -0,0 +1,109 @@
+use std::{borrow::Cow, fmt, marker::PhantomData};
+
+use serde::{
+    Deserialize, Deserializer,
+    de::{self, IgnoredAny, MapAccess, SeqAccess, Visitor},
+};
+
+/// The contents of an entry, which may turn out to be something other than a string.
+///
+/// An entry whose contents are not a string is disqualified, rather than rejecting the entire
+/// round file. Solvers from v06 onwards look at the raw JSON value instead and do not need this.
+pub(crate) enum Contents<S> {
+    Text(S),
+    NotAString,
+}
+
+impl<S: AsRef<str>> Contents<S> {
+    /// The text of the contents, `None` if the contents are not a string.
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text.as_ref()),
+            Self::NotAString => None,
+        }
+    }
+}
+
+/// String types that the contents can be deserialized into.
+pub(crate) trait ContentsText<'de> {
+    fn from_borrowed(text: &'de str) -> Self;
+    fn from_owned(text: String) -> Self;
+}
+
+impl ContentsText<'_> for String {
+    fn from_borrowed(text: &str) -> Self {
+        text.to_owned()
+    }
+
+    fn from_owned(text: String) -> Self {
+        text
+    }
+}
+
+impl<'de: 'a, 'a> ContentsText<'de> for Cow<'a, str> {
+    fn from_borrowed(text: &'de str) -> Self {
+        Cow::Borrowed(text)
+    }
+
+    fn from_owned(text: String) -> Self {
+        Cow::Owned(text)
+    }
+}
+
+impl<'de, S: ContentsText<'de>> Deserialize<'de> for Contents<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ContentsVisitor(PhantomData))
+    }
+}
+
+struct ContentsVisitor<S>(PhantomData<S>);
+
+impl<'de, S: ContentsText<'de>> Visitor<'de> for ContentsVisitor<S> {
+    type Value = Contents<S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Contents::Text(S::from_borrowed(v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Contents::Text(S::from_owned(v.to_owned())))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Contents::Text(S::from_owned(v)))
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(Contents::NotAString)
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(Contents::NotAString)
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(Contents::NotAString)
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(Contents::NotAString)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Contents::NotAString)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(Contents::NotAString)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+        Ok(Contents::NotAString)
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Reasons why a poetry contest data set could not be solved.
+#[derive(Debug)]
+pub enum ContestError {
+    /// The manifest could not be read, typically because it does not exist.
+    MissingManifest { path: PathBuf, source: io::Error },
+
+    /// A round file referenced by the manifest could not be read.
+    ///
+    /// The path is the round path exactly as listed in the manifest.
+    UnreadableRound { path: PathBuf, source: io::Error },
+
+    /// The manifest is not valid JSON or does not have the expected structure.
+    MalformedManifest {
+        line: usize,
+        column: usize,
+        source: serde_json::Error,
+    },
+
+    /// A round file is not valid JSON or does not have the expected structure.
+    ///
+    /// The path is the round path exactly as listed in the manifest.
+    MalformedRound {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        source: serde_json::Error,
+    },
+
+    /// The points awarded in a round make the score of an author, or the total of all scores,
+    /// impossible to represent exactly (see [`Score`][crate::Score]).
+    ///
+    /// The path is the round path exactly as listed in the manifest.
+    ScoreOverflow { round: PathBuf },
+
+    /// A place in a category is worth more points than fit into a `u64`, either in every round,
+    /// or only with the multiplier of the given round.
+    ///
+    /// The category is its index in the manifest, see
+    /// [`ContestResult::categories()`][crate::ContestResult::categories]. The path is the round
+    /// path exactly as listed in the manifest.
+    PointsOverflow {
+        category: usize,
+        round: Option<PathBuf>,
+    },
+}
+
+impl ContestError {
+    pub(crate) fn missing_manifest(path: &Path, source: io::Error) -> Self {
+        Self::MissingManifest {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub(crate) fn unreadable_round(path: &Path, source: io::Error) -> Self {
+        Self::UnreadableRound {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub(crate) fn malformed_manifest(source: serde_json::Error) -> Self {
+        Self::MalformedManifest {
+            line: source.line(),
+            column: source.column(),
+            source,
+        }
+    }
+
+    pub(crate) fn malformed_round(path: &Path, source: serde_json::Error) -> Self {
+        Self::MalformedRound {
+            path: path.to_path_buf(),
+            line: source.line(),
+            column: source.column(),
+            source,
+        }
+    }
+}
+
+impl Display for ContestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingManifest { path, source } => {
+                write!(f, "failed to read manifest {}: {source}", path.display())
+            }
+            Self::UnreadableRound { path, source } => {
+                write!(f, "failed to read round file {}: {source}", path.display())
+            }
+            // The serde_json error already includes the line and column.
+            Self::MalformedManifest { source, .. } => write!(f, "malformed manifest: {source}"),
+            Self::MalformedRound { path, source, .. } => {
+                write!(f, "malformed round file {}: {source}", path.display())
+            }
+            Self::ScoreOverflow { round } => write!(
+                f,
+                "score overflow in round file {}: a score can no longer be represented exactly",
+                round.display()
+            ),
+            Self::PointsOverflow {
+                category,
+                round: None,
+            } => write!(
+                f,
+                "points overflow: a place in category {category} is worth more points than fit \
+                 into 64 bits"
+            ),
+            Self::PointsOverflow {
+                category,
+                round: Some(round),
+            } => write!(
+                f,
+                "points overflow in round file {}: with its multiplier, a place in category \
+                 {category} is worth more points than fit into 64 bits",
+                round.display()
+            ),
+        }
+    }
+}
+
+impl Error for ContestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::MissingManifest { source, .. } | Self::UnreadableRound { source, .. } => {
+                Some(source)
+            }
+            Self::MalformedManifest { source, .. } | Self::MalformedRound { source, .. } => {
+                Some(source)
+            }
+            Self::ScoreOverflow { .. } | Self::PointsOverflow { .. } => None,
+        }
+    }
+}
+
+/// Reasons why a round could not be solved, turned into a [`ContestError`] by the caller, which
+/// knows the path of the round.
+pub(crate) enum RoundError {
+    Malformed(serde_json::Error),
+    ScoreOverflow,
+    PointsOverflow { category: usize },
+}
+
+impl RoundError {
+    pub(crate) fn in_round(self, path: &Path) -> ContestError {
+        match self {
+            Self::Malformed(source) => ContestError::malformed_round(path, source),
+            Self::ScoreOverflow => ContestError::ScoreOverflow {
+                round: path.to_path_buf(),
+            },
+            Self::PointsOverflow { category } => ContestError::PointsOverflow {
+                category,
+                round: Some(path.to_path_buf()),
+            },
+        }
+    }
+}
+
+impl From<serde_json::Error> for RoundError {
+    fn from(source: serde_json::Error) -> Self {
+        Self::Malformed(source)
+    }
+}
@@ -0,0 +1,31 @@
+//! Verification of solver results against the `expected.json` written by `generate_data`.
+
+use std::{fs, path::Path};
+
+use crate::{ContestResult, generator::Expected};
+
+/// Asserts that the result matches the expected result of the data set in `data_dir`, listing
+/// every author whose score differs if it does not.
+pub(crate) fn assert_matches_expected(data_dir: &Path, result: &ContestResult) {
+    let expected_path = data_dir.join("expected.json");
+
+    let expected_json = fs::read_to_string(&expected_path).unwrap_or_else(|e| {
+        panic!(
+            "Failed to read {}: {e}. Regenerate the data set by executing \
+             `cargo run --example generate_data --release`.",
+            expected_path.display()
+        )
+    });
+
+    let expected: Expected = serde_json::from_str(&expected_json)
+        .unwrap_or_else(|e| panic!("Malformed {}: {e}", expected_path.display()));
+
+    let differences = expected.differences(result);
+
+    assert!(
+        differences.is_empty(),
+        "Result differs from {}:\n{}",
+        expected_path.display(),
+        differences.join("\n")
+    );
+}
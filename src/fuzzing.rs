@@ -0,0 +1,65 @@
+//! Internals exposed to the fuzz targets in `fuzz/`. Not part of the public API.
+
+use serde_json::value::RawValue;
+
+use crate::{
+    DisqualificationReason, Rules, SolveOptions, TiePolicy, Weight, WordBoundary, judge::Judge,
+    scoring::EntryWeight, v06_raw_contents, v07_reuse_more, v08_faster_maps,
+};
+
+/// The functions a solver uses to decode the contents of entries itself, instead of leaving
+/// that to serde_json.
+pub struct ContentsDecoder {
+    /// Name of the solver, as returned by [`Solver::name()`][crate::Solver::name].
+    pub solver: &'static str,
+
+    pub calculate_json_string_length: fn(&RawValue) -> Result<usize, DisqualificationReason>,
+    calculate_weight: fn(&RawValue, &Judge) -> Result<EntryWeight, DisqualificationReason>,
+}
+
+impl ContentsDecoder {
+    /// Decodes the contents and calculates their weight, as the solver does for every entry in
+    /// some category, under the default rules.
+    pub fn calculate_weight(
+        &self,
+        raw: &RawValue,
+        word_boundary: WordBoundary,
+        weight: Weight,
+    ) -> Result<f64, DisqualificationReason> {
+        let options = SolveOptions {
+            word_boundary,
+            ..Default::default()
+        };
+        let rules = Rules::default();
+        let judge = Judge::new(
+            [] as [(&[&str], _); 0],
+            &rules,
+            weight.function(),
+            TiePolicy::default(),
+            None,
+            &options,
+        )
+        .expect("a contest without categories is always worth few enough points");
+
+        (self.calculate_weight)(raw, &judge).map(|weight| weight.primary)
+    }
+}
+
+/// Contents decoders of all the solvers that have one (v06 and later).
+pub static CONTENTS_DECODERS: [ContentsDecoder; 3] = [
+    ContentsDecoder {
+        solver: "v06_raw_contents",
+        calculate_json_string_length: v06_raw_contents::calculate_json_string_length,
+        calculate_weight: v06_raw_contents::calculate_weight,
+    },
+    ContentsDecoder {
+        solver: "v07_reuse_more",
+        calculate_json_string_length: v07_reuse_more::calculate_json_string_length,
+        calculate_weight: v07_reuse_more::calculate_weight,
+    },
+    ContentsDecoder {
+        solver: "v08_faster_maps",
+        calculate_json_string_length: v08_faster_maps::calculate_json_string_length,
+        calculate_weight: v08_faster_maps::calculate_weight,
+    },
+];
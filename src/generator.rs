@@ -0,0 +1,774 @@
+//! Generator of poetry contest data sets, used by the `generate_data` example and by tests.
+//!
+//! The same seed and sizes always yield byte-identical data sets. Along with the data, the
+//! generator calculates the result that the solvers are expected to return for it.
+//!
+//! Only available with the `generator` feature, which the examples and tests of this crate
+//! enable for themselves.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Write,
+    io,
+    path::PathBuf,
+};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+use crate::{ContestError, ContestResult, SolveOptions, Solver};
+
+/// Determines how large the generated data set is and what it contains.
+///
+/// The defaults produce the data set that the benchmarks are meant to be run against.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Sizes {
+    pub round_count: usize,
+    pub category_count: usize,
+    pub author_count: usize,
+    pub min_entries_per_round: usize,
+    pub max_entries_per_round: usize,
+    pub min_entry_words: usize,
+    pub max_entry_words: usize,
+    pub min_category_keywords: usize,
+    pub max_category_keywords: usize,
+
+    /// How many words there are on each line of an entry. The first line is the title.
+    pub line_length_words: usize,
+
+    /// How many words there are in the vocabulary we use.
+    ///
+    /// For simplify, we do not use real words, we just use integers as words. The first word is
+    /// "1", then "2", and so on up to `vocabulary_size`.
+    pub vocabulary_size: usize,
+
+    /// Percentage of entries (0-100) that are replaced with an adversarial edge case.
+    pub edge_case_percent: usize,
+
+    /// Percentage of keywords (0-100) that are phrases of two words instead of a single word.
+    pub phrase_keyword_percent: usize,
+
+    /// Percentage of categories (0-100) that have an id, a name and a description.
+    pub named_category_percent: usize,
+}
+
+impl Default for Sizes {
+    fn default() -> Self {
+        Self {
+            round_count: 500,
+            category_count: 50,
+            author_count: 250,
+            min_entries_per_round: 500,
+            max_entries_per_round: 2000,
+            min_entry_words: 50,
+            max_entry_words: 500,
+            min_category_keywords: 1,
+            max_category_keywords: 500,
+            line_length_words: 32,
+            vocabulary_size: 128_000,
+            edge_case_percent: 0,
+            phrase_keyword_percent: 0,
+            named_category_percent: 0,
+        }
+    }
+}
+
+impl Sizes {
+    fn validate(&self) {
+        assert!(self.author_count > 0, "author_count must be at least 1");
+        assert!(
+            self.line_length_words > 0,
+            "line_length_words must be at least 1"
+        );
+        assert!(
+            self.vocabulary_size > 0,
+            "vocabulary_size must be at least 1"
+        );
+        assert!(
+            self.edge_case_percent <= 100,
+            "edge_case_percent must not be greater than 100"
+        );
+        assert!(
+            self.phrase_keyword_percent <= 100,
+            "phrase_keyword_percent must not be greater than 100"
+        );
+        assert!(
+            self.named_category_percent <= 100,
+            "named_category_percent must not be greater than 100"
+        );
+
+        for (name, min, max) in [
+            (
+                "entries_per_round",
+                self.min_entries_per_round,
+                self.max_entries_per_round,
+            ),
+            ("entry_words", self.min_entry_words, self.max_entry_words),
+            (
+                "category_keywords",
+                self.min_category_keywords,
+                self.max_category_keywords,
+            ),
+        ] {
+            assert!(
+                min <= max,
+                "min_{name} ({min}) must not be greater than max_{name} ({max})"
+            );
+        }
+    }
+}
+
+/// Generates a data set one round at a time, so even huge data sets need not fit in memory.
+///
+/// The categories are generated up front. Each call to [`next_round()`][Self::next_round]
+/// generates one more round, until `round_count` rounds have been generated. The manifest and
+/// expected result cover the rounds generated so far.
+pub struct Generator {
+    sizes: Sizes,
+    rng: DataRng,
+    vocabulary: Vec<String>,
+    categories: Vec<Category>,
+    round_paths: Vec<PathBuf>,
+    entry_count: usize,
+    expected: Expected,
+}
+
+/// Random number generator used for all generated data.
+///
+/// We use ChaCha8 explicitly instead of `StdRng` because the algorithm behind `StdRng` may change
+/// between `rand` releases, which would silently change the data set generated from a given seed.
+type DataRng = ChaCha8Rng;
+
+/// One generated round, to be saved at `path` relative to the manifest.
+pub struct RoundFile {
+    pub path: PathBuf,
+    pub json: String,
+}
+
+impl Generator {
+    /// # Panics
+    ///
+    /// Panics if the sizes are inconsistent, e.g. a minimum is greater than the maximum.
+    pub fn new(seed: u64, sizes: Sizes) -> Self {
+        sizes.validate();
+
+        let mut generator = Self {
+            rng: DataRng::seed_from_u64(seed),
+            vocabulary: (1..=sizes.vocabulary_size)
+                .map(|word| word.to_string())
+                .collect(),
+            categories: Vec::with_capacity(sizes.category_count),
+            round_paths: Vec::with_capacity(sizes.round_count),
+            entry_count: 0,
+            expected: Expected::default(),
+            sizes,
+        };
+
+        for _ in 0..generator.sizes.category_count {
+            let category = generator.generate_category();
+            generator.categories.push(category);
+        }
+
+        generator
+    }
+
+    pub fn sizes(&self) -> &Sizes {
+        &self.sizes
+    }
+
+    /// How many rounds have been generated so far.
+    pub fn round_count(&self) -> usize {
+        self.round_paths.len()
+    }
+
+    /// How many entries the rounds generated so far contain in total.
+    pub fn entry_count(&self) -> usize {
+        self.entry_count
+    }
+
+    /// Generates the next round, named `round_<index>.json`, or returns `None` if all rounds
+    /// have already been generated.
+    pub fn next_round(&mut self) -> Option<RoundFile> {
+        if self.round_count() == self.sizes.round_count {
+            return None;
+        }
+
+        let round = self.generate_round();
+        self.entry_count += round.entries.len();
+        self.expected.score_round(&self.categories, &round);
+
+        let path = PathBuf::from(format!("round_{}.json", self.round_count()));
+        self.round_paths.push(path.clone());
+
+        let json = serde_json::to_string_pretty(&round).expect("Failed to serialize round");
+
+        Some(RoundFile { path, json })
+    }
+
+    /// The manifest, referencing all the rounds generated so far.
+    pub fn manifest_json(&self) -> String {
+        let manifest = Manifest {
+            categories: &self.categories,
+            rounds: &self.round_paths,
+        };
+
+        serde_json::to_string_pretty(&manifest).expect("Failed to serialize manifest")
+    }
+
+    /// The result that the solvers are expected to return for the rounds generated so far.
+    pub fn expected(&self) -> &Expected {
+        &self.expected
+    }
+
+    /// Generates all the remaining rounds and returns the entire data set.
+    pub fn finish(mut self) -> DataSet {
+        let mut rounds = HashMap::with_capacity(self.sizes.round_count);
+
+        while let Some(round) = self.next_round() {
+            rounds.insert(round.path, round.json);
+        }
+
+        DataSet {
+            manifest_json: self.manifest_json(),
+            rounds,
+            expected: self.expected,
+        }
+    }
+
+    fn word(&mut self) -> &str {
+        // There is more diversity in word length at the low end of the range (99 and 100 differ
+        // more significantly than 2099 and 2100), so we bias the distribution toward the low end
+        // by cubing. We multiply instead of using `powf`, whose result depends on the libm of the
+        // platform, as the same seed must yield the same data set everywhere.
+        let uniform = self.rng.random::<f64>();
+        let selector = uniform * uniform * uniform;
+        let word_index = (selector * (self.vocabulary.len() as f64)) as usize + 1;
+        self.vocabulary[word_index - 1].as_str()
+    }
+
+    fn generate_category(&mut self) -> Category {
+        let keyword_count = self
+            .rng
+            .random_range(self.sizes.min_category_keywords..=self.sizes.max_category_keywords);
+        let keywords: Vec<String> = (0..keyword_count)
+            .map(|_| self.generate_keyword())
+            .collect();
+
+        // As with edge cases, we only consult the RNG if named categories are enabled.
+        let is_named = self.sizes.named_category_percent > 0
+            && self.rng.random_range(0..100) < self.sizes.named_category_percent;
+
+        if !is_named {
+            return Category {
+                id: None,
+                name: None,
+                description: None,
+                keywords,
+            };
+        }
+
+        let index = self.categories.len();
+
+        Category {
+            id: Some(format!("category-{index}")),
+            name: Some(format!("Category {index}")),
+            // Quotes are escaped in JSON, so descriptions cannot be borrowed by the solvers.
+            description: Some(format!(
+                "Titles with any of the {} keywords of \"Category {index}\"",
+                keywords.len()
+            )),
+            keywords,
+        }
+    }
+
+    fn generate_keyword(&mut self) -> String {
+        // As with edge cases, we only consult the RNG if phrases are enabled.
+        let is_phrase = self.sizes.phrase_keyword_percent > 0
+            && self.rng.random_range(0..100) < self.sizes.phrase_keyword_percent;
+
+        let mut keyword = self.word().to_owned();
+
+        if is_phrase {
+            keyword.push(' ');
+            keyword.push_str(self.word());
+        }
+
+        keyword
+    }
+
+    fn generate_round(&mut self) -> Round {
+        let entry_count = self
+            .rng
+            .random_range(self.sizes.min_entries_per_round..=self.sizes.max_entries_per_round);
+        let mut entries = Vec::with_capacity(entry_count);
+
+        for _ in 0..entry_count {
+            // We only consult the RNG if edge cases are enabled, so that data sets without edge
+            // cases stay identical to those generated before edge cases existed.
+            let is_edge_case = self.sizes.edge_case_percent > 0
+                && self.rng.random_range(0..100) < self.sizes.edge_case_percent;
+
+            let entry = if is_edge_case {
+                self.generate_edge_case(&entries)
+            } else {
+                self.generate_entry()
+            };
+
+            entries.push(entry);
+        }
+
+        Round { entries }
+    }
+
+    fn generate_entry(&mut self) -> Entry {
+        // Generate author (random from 0 to author_count-1)
+        let author_index = self
+            .rng
+            .random_range(0..self.sizes.author_count)
+            .to_string();
+
+        let title = self.generate_title();
+
+        // Generate entry content (remaining words)
+        let word_count = self
+            .rng
+            .random_range(self.sizes.min_entry_words..=self.sizes.max_entry_words);
+        let content_word_count = word_count.saturating_sub(self.sizes.line_length_words);
+
+        let mut contents = String::with_capacity(1024);
+        for i in 0..content_word_count {
+            if i > 0 {
+                // Check if we should insert a line break
+                if i % self.sizes.line_length_words == 0 {
+                    contents.push('\n');
+                } else {
+                    contents.push(' ');
+                }
+            }
+
+            contents.push_str(self.word());
+        }
+
+        Entry {
+            author: author_index,
+            title,
+            contents: plain_json(&contents),
+            text: Some(contents),
+        }
+    }
+
+    fn generate_title(&mut self) -> String {
+        // Generate title (first line_length_words words)
+        let mut title = String::with_capacity(128);
+        for i in 0..self.sizes.line_length_words {
+            if i > 0 {
+                title.push(' ');
+            }
+            title.push_str(self.word());
+        }
+
+        title
+    }
+
+    fn generate_edge_case(&mut self, previous: &[Entry]) -> Entry {
+        let author = self
+            .rng
+            .random_range(0..self.sizes.author_count)
+            .to_string();
+
+        let edge_case = EdgeCase::ALL[self.rng.random_range(0..EdgeCase::ALL.len())];
+
+        if let EdgeCase::Tie = edge_case
+            && !previous.is_empty()
+        {
+            let original = &previous[self.rng.random_range(0..previous.len())];
+
+            return Entry {
+                author,
+                ..original.clone()
+            };
+        }
+
+        let title = self.generate_title();
+
+        let (contents, text) = match edge_case {
+            EdgeCase::Escapes | EdgeCase::Tie => {
+                const SPECIAL: &[&str] = &[
+                    "\"", "\\", "/", "\u{8}", "\u{c}", "\u{1}", "\u{7f}", "é", "ß", "€", "😀", "𝄞",
+                ];
+                const SEPARATORS: &[&str] = &[" ", "\n", "\t", "\r\n", "\u{a0}", "\u{3000}"];
+
+                let word_count = self
+                    .rng
+                    .random_range(self.sizes.min_entry_words..=self.sizes.max_entry_words);
+                let mut text = String::new();
+
+                for i in 0..word_count {
+                    if i > 0 {
+                        text.push_str(SEPARATORS[self.rng.random_range(0..SEPARATORS.len())]);
+                    }
+
+                    text.push_str(self.word());
+
+                    if self.rng.random_bool(0.5) {
+                        text.push_str(SPECIAL[self.rng.random_range(0..SPECIAL.len())]);
+                    }
+                }
+
+                (escaped_json(&text), Some(text))
+            }
+            EdgeCase::Whitespace => {
+                const WHITESPACE: &[char] = &[
+                    ' ', '\n', '\t', '\r', '\u{b}', '\u{c}', '\u{a0}', '\u{2003}', '\u{3000}',
+                ];
+
+                let text = (0..self.rng.random_range(0..20))
+                    .map(|_| WHITESPACE[self.rng.random_range(0..WHITESPACE.len())])
+                    .collect::<String>();
+
+                (self.any_json(&text), Some(text))
+            }
+            EdgeCase::MaxLength => {
+                let target = MAX_CONTENTS_LENGTH + self.rng.random_range(0..=1);
+                let mut text = String::with_capacity(target);
+
+                loop {
+                    let separator_len = usize::from(!text.is_empty());
+                    let word = self.word();
+
+                    if text.len() + separator_len + word.len() > target {
+                        break;
+                    }
+
+                    if separator_len > 0 {
+                        text.push(' ');
+                    }
+
+                    text.push_str(word);
+                }
+
+                // Pad the last word to exactly the target length, also using multi-byte characters.
+                while text.len() < target {
+                    if target - text.len() >= 'é'.len_utf8() && self.rng.random_bool(0.5) {
+                        text.push('é');
+                    } else {
+                        text.push('x');
+                    }
+                }
+
+                (self.any_json(&text), Some(text))
+            }
+            EdgeCase::NotAString => {
+                const VALUES: &[&str] = &[
+                    "null",
+                    "true",
+                    "0",
+                    "12.5",
+                    "[]",
+                    "[\"1 2 3\", 4]",
+                    "{}",
+                    "{\"contents\": \"1 2 3\"}",
+                ];
+
+                let value = VALUES[self.rng.random_range(0..VALUES.len())];
+
+                let value = RawValue::from_string(value.to_owned())
+                    .expect("Edge case values are valid JSON");
+
+                (value, None)
+            }
+        };
+
+        Entry {
+            author,
+            title,
+            contents,
+            text,
+        }
+    }
+
+    /// Encodes the text as a JSON string either plainly or with everything escaped, at random.
+    fn any_json(&mut self, text: &str) -> Box<RawValue> {
+        if self.rng.random_bool(0.5) {
+            escaped_json(text)
+        } else {
+            plain_json(text)
+        }
+    }
+}
+
+/// A complete generated data set, held in memory.
+pub struct DataSet {
+    pub manifest_json: String,
+
+    /// JSON of each round, keyed by the path listed in the manifest.
+    pub rounds: HashMap<PathBuf, String>,
+
+    pub expected: Expected,
+}
+
+impl DataSet {
+    /// Solves the data set with the given solver without touching the filesystem.
+    pub fn solve(
+        &self,
+        solver: &dyn Solver,
+        options: &SolveOptions,
+    ) -> Result<ContestResult, ContestError> {
+        solver.solve_str_with(
+            &self.manifest_json,
+            &mut |path, round_json| {
+                let json = self.rounds.get(path).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, "round is not in the data set")
+                })?;
+
+                round_json.push_str(json);
+                Ok(())
+            },
+            options,
+        )
+    }
+}
+
+/// The result that the solvers are expected to return for a generated data set.
+///
+/// This is calculated from the generated data in memory, independently of the solvers, as
+/// straightforwardly as possible. If a solver disagrees, the solver is the one assumed wrong.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Expected {
+    /// Total score of all authors.
+    pub total: u64,
+
+    /// Score of each author. Authors that did not receive any points are not present.
+    pub scores: BTreeMap<String, u64>,
+}
+
+impl Expected {
+    /// Describes every author whose score in the result differs from the expected one, as well
+    /// as a differing total. Empty if the result is as expected.
+    pub fn differences(&self, result: &ContestResult) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        let authors = self
+            .scores
+            .keys()
+            .chain(result.scores().keys())
+            .collect::<BTreeSet<_>>();
+
+        for author in authors {
+            let expected_score = self.scores.get(author).copied().unwrap_or_default();
+            let actual_score = result.score(author);
+
+            if actual_score != expected_score {
+                differences.push(format!(
+                    "author {author}: expected {expected_score}, got {actual_score}"
+                ));
+            }
+        }
+
+        if result.total() != self.total {
+            differences.push(format!(
+                "total: expected {}, got {}",
+                self.total,
+                result.total()
+            ));
+        }
+
+        differences
+    }
+
+    /// Awards the points of one round, following the scoring rules documented on the solvers.
+    fn score_round(&mut self, categories: &[Category], round: &Round) {
+        // For each category, the highest weight and the authors of the entries with that weight.
+        let mut best: Vec<Option<(f64, BTreeSet<&str>)>> = vec![None; categories.len()];
+
+        for entry in &round.entries {
+            let Some(text) = &entry.text else {
+                continue;
+            };
+
+            if text.len() > MAX_CONTENTS_LENGTH || text.trim().is_empty() {
+                continue;
+            }
+
+            let weight = text.len() as f64 / text.split_whitespace().count() as f64;
+            let title_words = entry.title.split_whitespace().collect::<Vec<_>>();
+            let title_word_set = title_words.iter().copied().collect::<HashSet<_>>();
+
+            for (category, best) in categories.iter().zip(&mut best) {
+                let matches = category.keywords.iter().any(|keyword| {
+                    if !keyword.contains(' ') {
+                        return title_word_set.contains(keyword.as_str());
+                    }
+
+                    // A phrase matches if the title contains its words consecutively.
+                    let keyword_words = keyword.split_whitespace().collect::<Vec<_>>();
+                    title_words
+                        .windows(keyword_words.len())
+                        .any(|window| window == keyword_words)
+                });
+
+                if !matches {
+                    continue;
+                }
+
+                match best {
+                    Some((best_weight, authors)) if weight == *best_weight => {
+                        authors.insert(&entry.author);
+                    }
+                    Some((best_weight, _)) if weight < *best_weight => {}
+                    _ => *best = Some((weight, BTreeSet::from([entry.author.as_str()]))),
+                }
+            }
+        }
+
+        for (_, authors) in best.into_iter().flatten() {
+            for author in authors {
+                *self.scores.entry(author.to_owned()).or_default() += 1;
+                self.total += 1;
+            }
+        }
+    }
+}
+
+/// The manifest is the root object of the data set.
+///
+/// It defines all the metadata and references all the other files that make up the data set.
+///
+/// This is the manifest of one poetry contest, whereby entries from different rounds are evaluated
+/// against different categories, with the authors gaining points based on the scores they receive
+/// in each round of the contest.
+#[derive(Serialize)]
+struct Manifest<'a> {
+    categories: &'a [Category],
+    /// Relative paths from the directory of the manifest file to the round JSON files.
+    rounds: &'a [PathBuf],
+}
+
+/// One category that entries are evaluated against.
+///
+/// A category is simply a set of keywords that are used to identify entries
+/// that belong in that category, optionally with some metadata for people.
+#[derive(Serialize)]
+struct Category {
+    /// Only present in `named_category_percent` of the categories, as are the name and the
+    /// description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    /// Keywords that define the category.
+    ///
+    /// Between `min_category_keywords` and `max_category_keywords` keywords in each category,
+    /// randomly chosen from the vocabulary. Some keywords may be phrases of two words separated
+    /// by a space, see `phrase_keyword_percent`.
+    keywords: Vec<String>,
+}
+
+/// One round of the contest.
+#[derive(Serialize)]
+struct Round {
+    /// All the entries that compete in the round.
+    ///
+    /// Between `min_entries_per_round` and `max_entries_per_round` entries in each round, random.
+    entries: Vec<Entry>,
+}
+
+#[derive(Clone, Serialize)]
+struct Entry {
+    /// Name of the author - the person that any scoring is attributed to.
+    author: String,
+
+    /// The title of the entry, used for category matching.
+    title: String,
+
+    /// The actual text content of the entry.
+    ///
+    /// Normally a JSON string but edge cases may use escape sequences that serde_json would not
+    /// generate or even some other JSON value entirely, so we write the raw JSON ourselves.
+    contents: Box<RawValue>,
+
+    /// The contents as text, `None` if the contents are not a JSON string.
+    #[serde(skip)]
+    text: Option<String>,
+}
+
+/// Kinds of adversarial entries that exercise the edge paths of the solvers.
+///
+/// Invalid escape sequences (e.g. unpaired surrogates) are deliberately not generated because
+/// the solvers that let serde_json decode the contents reject the entire round file for them.
+#[derive(Clone, Copy)]
+enum EdgeCase {
+    /// Contents that use every kind of JSON escape sequence, including `\uXXXX` surrogate pairs
+    /// and characters that are not normally escaped.
+    Escapes,
+
+    /// Contents that are empty or consist only of (possibly non-ASCII) whitespace.
+    Whitespace,
+
+    /// Contents that are exactly 1000 bytes long - the longest allowed - or one byte longer.
+    MaxLength,
+
+    /// Contents that are a JSON value other than a string.
+    NotAString,
+
+    /// A copy of an earlier entry of the round attributed to a random author, so both entries
+    /// have exactly the same weight and categories. The author may also be the same one.
+    Tie,
+}
+
+impl EdgeCase {
+    const ALL: [Self; 5] = [
+        Self::Escapes,
+        Self::Whitespace,
+        Self::MaxLength,
+        Self::NotAString,
+        Self::Tie,
+    ];
+}
+
+/// Longest allowed contents, in bytes.
+const MAX_CONTENTS_LENGTH: usize = 1000;
+
+/// Encodes the text as a JSON string the way serde_json does, escaping only what must be escaped.
+fn plain_json(text: &str) -> Box<RawValue> {
+    serde_json::value::to_raw_value(text).expect("Failed to serialize string")
+}
+
+/// Encodes the text as a JSON string, escaping everything that JSON allows to be escaped except
+/// printable ASCII. Non-ASCII characters become `\uXXXX` escapes, with surrogate pairs where
+/// needed.
+fn escaped_json(text: &str) -> Box<RawValue> {
+    let mut json = String::with_capacity(text.len() * 6 + 2);
+    json.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '/' => json.push_str("\\/"),
+            '\u{8}' => json.push_str("\\b"),
+            '\u{c}' => json.push_str("\\f"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ' '..='~' => json.push(c),
+            _ => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(json, "\\u{unit:04X}").expect("Writing to a String cannot fail");
+                }
+            }
+        }
+    }
+
+    json.push('"');
+
+    RawValue::from_string(json).expect("Escaped string is valid JSON")
+}
@@ -0,0 +1,217 @@
+//! Everything that decides which categories an entry matches and what weight it has, shared by
+//! all the solvers.
+
+use std::{collections::HashSet, num::NonZeroU64};
+
+use crate::{
+    ContestError, DisqualificationReason, KeywordMatching, Rules, SolveOptions, TiePolicy,
+    WeightFunction, WordBoundary, error::RoundError, phrases::PhraseMatcher, scoring::EntryWeight,
+};
+
+/// How the entries of one contest are judged, resolved once from the manifest and the options
+/// and then used for every round.
+///
+/// Looking up single-word keywords is left to the solvers, as that is where they differ most.
+pub(crate) struct Judge<'a> {
+    pub(crate) word_boundary: WordBoundary,
+    pub(crate) keyword_matching: &'a KeywordMatching,
+
+    /// Keywords of more than one word never match a single word of a title, so they are found
+    /// separately, by looking for their words in consecutive words of the title.
+    pub(crate) phrases: PhraseMatcher,
+
+    /// Longest allowed contents, in bytes. Checked by the solvers, as they find out the length
+    /// in different ways, and some before the contents are even decoded.
+    pub(crate) max_bytes: usize,
+
+    rules: &'a Rules,
+
+    /// The banned words of a single word, in the form they are matched in.
+    banned_words: HashSet<String>,
+
+    /// The banned words of more than one word.
+    banned_phrases: PhraseMatcher,
+
+    pub(crate) tie_policy: TiePolicy,
+
+    /// Points that a win in each category is worth, before the multiplier of the round.
+    category_points: Vec<u64>,
+
+    /// Points that each place on the podium of a category is worth, relative to the points of
+    /// the category. Only one place, worth the points of the category, by default.
+    podium: Vec<u64>,
+
+    /// The most points that any place in any category is worth, before the multiplier of the
+    /// round, and the index of a category whose first place is worth that many. As long as this
+    /// times the multiplier fits into a `u64`, so do all the points.
+    max_points: (u64, usize),
+
+    weight_function: &'a dyn WeightFunction,
+
+    /// Only needed if the tie policy compares tied entries by another weight.
+    secondary_weight_function: Option<&'static dyn WeightFunction>,
+}
+
+impl<'a> Judge<'a> {
+    /// Prepares to judge a contest, given the keywords and the points of each category, the
+    /// rules, the weight function selected by the manifest, unless the options override it, the
+    /// tie policy and the podium, which has at least one place if set.
+    ///
+    /// Fails if a place in a category would be worth more points than fit into a `u64`.
+    pub(crate) fn new<'c, K: AsRef<str> + 'c>(
+        categories: impl IntoIterator<Item = (&'c [K], Option<NonZeroU64>)>,
+        rules: &'a Rules,
+        weight_function: &'a dyn WeightFunction,
+        tie_policy: TiePolicy,
+        podium: Option<&[NonZeroU64]>,
+        options: &'a SolveOptions,
+    ) -> Result<Self, ContestError> {
+        let (keywords, category_points): (Vec<_>, Vec<_>) = categories
+            .into_iter()
+            .map(|(keywords, points)| (keywords, points.map_or(1, NonZeroU64::get)))
+            .unzip();
+
+        let banned_words = rules
+            .banned_words
+            .iter()
+            .map(|word| options.keyword_matching.keyword(word).into_owned())
+            .collect();
+
+        let podium = podium.map_or_else(
+            || vec![1],
+            |podium| podium.iter().copied().map(NonZeroU64::get).collect(),
+        );
+
+        let podium_max = podium.iter().max().copied().unwrap_or(1);
+        let mut max_points = (0, 0);
+
+        for (category, &points) in category_points.iter().enumerate() {
+            let points = podium_max
+                .checked_mul(points)
+                .ok_or(ContestError::PointsOverflow {
+                    category,
+                    round: None,
+                })?;
+
+            if points > max_points.0 {
+                max_points = (points, category);
+            }
+        }
+
+        Ok(Self {
+            word_boundary: options.word_boundary,
+            keyword_matching: &options.keyword_matching,
+            phrases: PhraseMatcher::new(keywords, options),
+            max_bytes: rules.max_bytes,
+            rules,
+            banned_words,
+            banned_phrases: PhraseMatcher::new([&rules.banned_words[..]], options),
+            tie_policy,
+            category_points,
+            podium,
+            max_points,
+            weight_function: options
+                .weight_function
+                .as_deref()
+                .unwrap_or(weight_function),
+            secondary_weight_function: match tie_policy {
+                TiePolicy::SecondaryWeight(weight) => Some(weight.function()),
+                _ => None,
+            },
+        })
+    }
+
+    /// Number of places on the podium of each category.
+    pub(crate) fn places(&self) -> usize {
+        self.podium.len()
+    }
+
+    /// Resolves the multiplier of a round, 1 if the round does not set one.
+    ///
+    /// Fails if a place in a category would be worth more points than fit into a `u64` in the
+    /// round.
+    pub(crate) fn multiplier(&self, multiplier: Option<NonZeroU64>) -> Result<u64, RoundError> {
+        let multiplier = multiplier.map_or(1, NonZeroU64::get);
+        let (max_points, category) = self.max_points;
+
+        match max_points.checked_mul(multiplier) {
+            Some(_) => Ok(multiplier),
+            None => Err(RoundError::PointsOverflow { category }),
+        }
+    }
+
+    /// Points that a place in the category is worth in a round with the given multiplier, as
+    /// resolved by [`multiplier()`][Self::multiplier].
+    pub(crate) fn points(&self, cat_idx: usize, place: usize, multiplier: u64) -> u64 {
+        // Cannot overflow, as checked when resolving the multiplier.
+        self.podium[place] * self.category_points[cat_idx] * multiplier
+    }
+
+    /// Checks the contents against all the rules except the length in bytes, which the caller
+    /// has already checked, and returns the reason for disqualification if they break any.
+    pub(crate) fn check(&self, contents: &str) -> Result<(), DisqualificationReason> {
+        if !self.word_boundary.has_words(contents) {
+            return Err(DisqualificationReason::Empty);
+        }
+
+        if let Some(max_chars) = self.rules.max_chars
+            && contents.chars().count() > max_chars
+        {
+            return Err(DisqualificationReason::TooManyChars);
+        }
+
+        if self.rules.min_words.is_some() || self.rules.max_words.is_some() {
+            let word_count = self.word_boundary.word_count(contents);
+
+            if self.rules.min_words.is_some_and(|min| word_count < min) {
+                return Err(DisqualificationReason::TooFewWords);
+            }
+
+            if self.rules.max_words.is_some_and(|max| word_count > max) {
+                return Err(DisqualificationReason::TooManyWords);
+            }
+        }
+
+        if let Some(max_lines) = self.rules.max_lines
+            && contents.lines().count() > max_lines
+        {
+            return Err(DisqualificationReason::TooManyLines);
+        }
+
+        if !self.banned_words.is_empty()
+            && self.word_boundary.words(contents).any(|word| {
+                self.banned_words
+                    .contains(&*self.keyword_matching.title_word(word))
+            })
+        {
+            return Err(DisqualificationReason::BannedWord);
+        }
+
+        let mut banned_phrases = Vec::new();
+        self.banned_phrases
+            .match_categories(contents, &mut banned_phrases);
+        if !banned_phrases.is_empty() {
+            return Err(DisqualificationReason::BannedWord);
+        }
+
+        Ok(())
+    }
+
+    /// The weight of an entry with the given contents, which must have passed [`Self::check`].
+    pub(crate) fn weight(&self, contents: &str) -> EntryWeight {
+        let weight = EntryWeight {
+            primary: self.weight_function.weight(contents, self.word_boundary),
+            secondary: self.secondary_weight_function.map_or(0.0, |function| {
+                function.weight(contents, self.word_boundary)
+            }),
+        };
+
+        // The podium could not rank such an entry consistently, see `WeightFunction::weight()`.
+        debug_assert!(
+            !weight.primary.is_nan() && !weight.secondary.is_nan(),
+            "weight function returned NaN"
+        );
+
+        weight
+    }
+}
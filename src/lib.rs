@@ -1,5 +1,23 @@
 use std::path::PathBuf;
 
+mod contents;
+mod error;
+#[cfg(test)]
+mod expected;
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing;
+#[cfg(feature = "generator")]
+pub mod generator;
+mod judge;
+mod options;
+mod phrases;
+mod result;
+mod rules;
+mod score;
+mod scoring;
+pub mod solver;
+mod text;
 pub mod v01_simple;
 pub mod v02_less_cloning;
 pub mod v03_borrow_document;
@@ -8,6 +26,22 @@ pub mod v05_reserve_and_reuse;
 pub mod v06_raw_contents;
 pub mod v07_reuse_more;
 pub mod v08_faster_maps;
+mod weight;
+
+pub use error::ContestError;
+pub use options::SolveOptions;
+pub use result::{
+    CategoryInfo, CategoryWinners, ContestResult, Disqualification, DisqualificationReason,
+    LeaderboardEntry, RoundDetails,
+};
+pub use rules::Rules;
+pub use score::Score;
+pub use scoring::TiePolicy;
+pub use solver::{RoundLoader, Solver, find_solver, solvers};
+pub use text::{KeywordMatching, NormalizationForm, Punctuation, WordBoundary, Words};
+pub use weight::{
+    CharDensity, DistinctWordRatio, LineCount, SyllableDensity, Weight, WeightFunction, WordDensity,
+};
 
 pub fn find_workspace_root() -> PathBuf {
     let mut current = std::env::current_dir().expect("Failed to get current directory");
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use crate::{KeywordMatching, WeightFunction, WordBoundary};
+
+/// Options that control how a solver interprets the data set and what it records in addition
+/// to the score of each author.
+///
+/// Everything is disabled by default and words are split as described in the scoring rules, which
+/// is what the benchmarks measure.
+#[derive(Clone, Debug, Default)]
+pub struct SolveOptions {
+    /// Whether to record the winners of every category in every round, exposed via
+    /// [`ContestResult::rounds()`][crate::ContestResult::rounds].
+    pub round_details: bool,
+
+    /// Whether to record every disqualified entry together with the reason for disqualification,
+    /// exposed via [`ContestResult::disqualifications()`][crate::ContestResult::disqualifications].
+    pub disqualifications: bool,
+
+    /// How titles and contents are split into words, see [`WordBoundary`].
+    pub word_boundary: WordBoundary,
+
+    /// When a word of a title matches a keyword, see [`KeywordMatching`].
+    pub keyword_matching: KeywordMatching,
+
+    /// Calculates the weight of the entries instead of the [`Weight`][crate::Weight] selected
+    /// by the manifest, if set.
+    pub weight_function: Option<Arc<dyn WeightFunction>>,
+}
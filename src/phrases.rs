@@ -0,0 +1,204 @@
+//! Matching of keywords that consist of more than one word, shared by all the solvers.
+//!
+//! A keyword like "autumn leaves" can never match a single word of a title, it matches if the
+//! title contains its words consecutively. The solvers look up single words in their own keyword
+//! maps and leave phrases to the [`PhraseMatcher`].
+
+use std::collections::HashMap;
+
+use aho_corasick::AhoCorasick;
+
+use crate::{KeywordMatching, SolveOptions, WordBoundary};
+
+/// Finds the categories whose phrase keywords occur in a title.
+///
+/// Every distinct word of every phrase is assigned a token, which we encode as a `char`. A phrase
+/// becomes a string of tokens and a title becomes a string of the tokens of its words, with a
+/// separator for words that are not part of any phrase. Finding all phrases in a title is then a
+/// multi-pattern string search, for which we use Aho-Corasick, so the time it takes does not
+/// depend on the number of phrases. As UTF-8 is self-synchronizing, every match starts and ends
+/// at a token boundary.
+pub(crate) struct PhraseMatcher {
+    word_boundary: WordBoundary,
+    keyword_matching: KeywordMatching,
+
+    /// `None` if no category has a phrase keyword, which is the common case.
+    searcher: Option<Searcher>,
+}
+
+struct Searcher {
+    /// Key: word of some phrase in the form it is matched in, Value: its token.
+    tokens: HashMap<String, char>,
+
+    /// Finds the phrases, identified by their index in `categories`.
+    automaton: AhoCorasick,
+
+    /// Indices of the categories that contain each phrase.
+    categories: Vec<Vec<usize>>,
+}
+
+/// Stands for every title word that is not part of any phrase. Never a token.
+const SEPARATOR: char = '\0';
+
+impl PhraseMatcher {
+    /// Collects the phrase keywords of all categories, given the keywords of each category.
+    pub(crate) fn new<'c, K: AsRef<str> + 'c>(
+        categories: impl IntoIterator<Item = &'c [K]>,
+        options: &SolveOptions,
+    ) -> Self {
+        let mut tokens = HashMap::new();
+
+        // Key: phrase as a string of tokens, Value: indices of the categories that contain it.
+        let mut phrases: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (cat_idx, keywords) in categories.into_iter().enumerate() {
+            for keyword in keywords {
+                let words = options
+                    .word_boundary
+                    .words(keyword.as_ref())
+                    .map(|word| options.keyword_matching.keyword(word))
+                    .collect::<Vec<_>>();
+
+                if words.len() < 2 {
+                    continue;
+                }
+
+                let phrase = words
+                    .into_iter()
+                    .map(|word| {
+                        let next_token = token(tokens.len());
+                        *tokens.entry(word.into_owned()).or_insert(next_token)
+                    })
+                    .collect::<String>();
+
+                let phrase_categories = phrases.entry(phrase).or_default();
+                if !phrase_categories.contains(&cat_idx) {
+                    phrase_categories.push(cat_idx);
+                }
+            }
+        }
+
+        let searcher = (!phrases.is_empty()).then(|| {
+            let (patterns, categories): (Vec<_>, Vec<_>) = phrases.into_iter().unzip();
+
+            Searcher {
+                tokens,
+                automaton: AhoCorasick::new(patterns).expect("phrases are never too large"),
+                categories,
+            }
+        });
+
+        Self {
+            word_boundary: options.word_boundary,
+            keyword_matching: options.keyword_matching.clone(),
+            searcher,
+        }
+    }
+
+    /// Adds the categories whose phrase keywords occur in `title` to `matched_categories`,
+    /// unless they are already present.
+    pub(crate) fn match_categories(&self, title: &str, matched_categories: &mut Vec<usize>) {
+        let Some(searcher) = &self.searcher else {
+            return;
+        };
+
+        let haystack = self
+            .word_boundary
+            .words(title)
+            .map(|word| {
+                let word = self.keyword_matching.title_word(word);
+                searcher.tokens.get(&*word).copied().unwrap_or(SEPARATOR)
+            })
+            .collect::<String>();
+
+        for found in searcher.automaton.find_overlapping_iter(&haystack) {
+            for &cat_idx in &searcher.categories[found.pattern().as_usize()] {
+                if !matched_categories.contains(&cat_idx) {
+                    matched_categories.push(cat_idx);
+                }
+            }
+        }
+    }
+}
+
+/// The token of the word with the given index, skipping the separator and surrogates.
+fn token(index: usize) -> char {
+    const SURROGATES: u32 = 0xE000 - 0xD800;
+
+    let mut code = u32::try_from(index).expect("too many distinct words in phrases") + 1;
+    if code >= 0xD800 {
+        code += SURROGATES;
+    }
+
+    char::from_u32(code).expect("too many distinct words in phrases")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Punctuation;
+
+    fn matched(matcher: &PhraseMatcher, title: &str) -> Vec<usize> {
+        let mut matched_categories = Vec::new();
+        matcher.match_categories(title, &mut matched_categories);
+        matched_categories.sort_unstable();
+        matched_categories
+    }
+
+    #[test]
+    fn phrases_match_consecutive_words() {
+        let categories = [
+            vec!["autumn leaves", "leaves"],
+            vec!["falling leaves", "autumn"],
+            vec!["leaves falling autumn"],
+        ];
+        let matcher = PhraseMatcher::new(
+            categories.iter().map(Vec::as_slice),
+            &SolveOptions::default(),
+        );
+
+        // Single words are left to the solvers.
+        assert_eq!(matched(&matcher, "leaves autumn"), [] as [usize; 0]);
+        assert_eq!(matched(&matcher, "autumn  \n leaves"), [0]);
+        assert_eq!(matched(&matcher, "autumn the leaves"), [] as [usize; 0]);
+        assert_eq!(matched(&matcher, "falling autumn leaves"), [0]);
+        assert_eq!(matched(&matcher, "leaves falling autumn leaves"), [0, 2]);
+        assert_eq!(matched(&matcher, "autumn leaves, falling leaves"), [1]);
+    }
+
+    #[test]
+    fn phrases_follow_word_boundary_and_keyword_matching() {
+        let categories = [vec!["autumn\u{a0}leaves"], vec!["Rock-n-Roll"]];
+        let options = SolveOptions {
+            word_boundary: WordBoundary::UnicodeWords,
+            keyword_matching: KeywordMatching {
+                case_folding: true,
+                trim_punctuation: Some(Punctuation::default()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let matcher = PhraseMatcher::new(categories.iter().map(Vec::as_slice), &options);
+
+        assert_eq!(matched(&matcher, "Autumn leaves"), [0]);
+        assert_eq!(matched(&matcher, "rock n roll"), [1]);
+        assert_eq!(matched(&matcher, "\"Rock\"-n-roll!"), [1]);
+
+        // Under ASCII whitespace, neither keyword is a phrase.
+        let matcher = PhraseMatcher::new(
+            categories.iter().map(Vec::as_slice),
+            &SolveOptions {
+                word_boundary: WordBoundary::AsciiWhitespace,
+                ..Default::default()
+            },
+        );
+        assert_eq!(matched(&matcher, "autumn leaves"), [] as [usize; 0]);
+    }
+
+    #[test]
+    fn tokens_are_never_separators_or_surrogates() {
+        assert_ne!(token(0), SEPARATOR);
+        assert_eq!(token(0xD7FE), '\u{d7ff}');
+        assert_eq!(token(0xD7FF), '\u{e000}');
+    }
+}
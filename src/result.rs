@@ -0,0 +1,464 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{Score, SolveOptions, error::RoundError, scoring::Place};
+
+/// Outcome of a poetry contest: the score of every author that received at least one point,
+/// optionally accompanied by details on how the points were awarded.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContestResult {
+    scores: BTreeMap<String, Score>,
+    total: Score,
+    categories: Option<Vec<CategoryInfo>>,
+    rounds: Option<Vec<RoundDetails>>,
+    disqualifications: Option<Vec<Disqualification>>,
+}
+
+impl ContestResult {
+    /// Score of each author, keyed by author name.
+    ///
+    /// Authors that did not receive any points are not present.
+    pub fn scores(&self) -> &BTreeMap<String, Score> {
+        &self.scores
+    }
+
+    /// Score of one author, zero if the author did not receive any points.
+    pub fn score(&self, author: &str) -> Score {
+        self.scores.get(author).copied().unwrap_or_default()
+    }
+
+    /// Total score of all authors.
+    pub fn total(&self) -> Score {
+        self.total
+    }
+
+    /// All authors with their scores, ordered from the highest score to the lowest.
+    ///
+    /// Authors with equal scores share the same rank and are ordered by name. Ranks are dense,
+    /// so the rank after a tie is always one greater than the rank of the tied authors.
+    pub fn leaderboard(&self) -> Vec<LeaderboardEntry<'_>> {
+        let mut by_score = self.scores.iter().collect::<Vec<_>>();
+
+        // The map is already ordered by name and the sort is stable, so ties stay ordered by name.
+        by_score.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let mut leaderboard = Vec::with_capacity(by_score.len());
+        let mut rank = 0;
+        let mut previous_score = None;
+
+        for (author, &score) in by_score {
+            if previous_score != Some(score) {
+                rank += 1;
+                previous_score = Some(score);
+            }
+
+            leaderboard.push(LeaderboardEntry {
+                rank,
+                author,
+                score,
+            });
+        }
+
+        leaderboard
+    }
+
+    /// Every category of the manifest with its metadata, in the order they are listed in the
+    /// manifest, so that the `category` index of [`CategoryWinners`] can be resolved.
+    ///
+    /// Only available if requested via [`SolveOptions::round_details`].
+    pub fn categories(&self) -> Option<&[CategoryInfo]> {
+        self.categories.as_deref()
+    }
+
+    /// Winners of every category in every round, in the order the rounds are listed in the
+    /// manifest.
+    ///
+    /// Only available if requested via [`SolveOptions::round_details`].
+    pub fn rounds(&self) -> Option<&[RoundDetails]> {
+        self.rounds.as_deref()
+    }
+
+    /// Every disqualified entry of every round, ordered by round and then by entry.
+    ///
+    /// Only available if requested via [`SolveOptions::disqualifications`].
+    pub fn disqualifications(&self) -> Option<&[Disqualification]> {
+        self.disqualifications.as_deref()
+    }
+}
+
+/// # Panics
+///
+/// If the total of the scores cannot be represented exactly, which the solvers never return.
+impl<A: Into<String>, S: Into<Score>> FromIterator<(A, S)> for ContestResult {
+    fn from_iter<I: IntoIterator<Item = (A, S)>>(iter: I) -> Self {
+        let scores = iter
+            .into_iter()
+            .map(|(author, score)| (author.into(), score.into()))
+            .collect::<BTreeMap<_, _>>();
+
+        Self {
+            total: scores.values().sum(),
+            scores,
+            categories: None,
+            rounds: None,
+            disqualifications: None,
+        }
+    }
+}
+
+/// One category of the manifest. Apart from its index, everything is optional metadata that
+/// the manifest may give to make the category easier to recognize than by its keywords.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CategoryInfo {
+    /// Position of the category in the manifest.
+    pub index: usize,
+
+    /// Short identifier of the category, meant to be stable across contests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Name of the category, meant for people.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl CategoryInfo {
+    /// What to call the category when showing it to people: its name, otherwise its id,
+    /// otherwise its index like `#3`.
+    pub fn label(&self) -> Cow<'_, str> {
+        match (&self.name, &self.id) {
+            (Some(name), _) => Cow::Borrowed(name),
+            (None, Some(id)) => Cow::Borrowed(id),
+            (None, None) => Cow::Owned(format!("#{}", self.index)),
+        }
+    }
+}
+
+/// Winners of the categories of one round.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RoundDetails {
+    /// Position of the round in the manifest.
+    pub index: usize,
+
+    /// Path of the round file, exactly as listed in the manifest.
+    pub path: PathBuf,
+
+    /// Winners of each place of each category that at least one entry of the round matched,
+    /// ordered by category index and then by place. Categories that no entry matched are not
+    /// present, nor are places that no entry reached.
+    pub categories: Vec<CategoryWinners>,
+}
+
+/// The entries that won one place of one category in one round, each of which yields points
+/// for its author.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CategoryWinners {
+    /// Index of the category in the manifest, see [`ContestResult::categories()`].
+    pub category: usize,
+
+    /// Place of the winning entries on the podium of the category, 1 for the highest weight.
+    /// Unless the manifest has a `podium`, there is only one place.
+    pub place: usize,
+
+    /// The weight of the winning entries.
+    pub weight: f64,
+
+    /// Authors of the winning entries, each listed once even if they have multiple winning
+    /// entries in the category. Authors with a better place in the category are not listed.
+    ///
+    /// Empty if the entries were tied and the [`TiePolicy`][crate::TiePolicy] awarded nobody,
+    /// or if all of their authors have a better place.
+    pub authors: Vec<String>,
+
+    /// Indices of the winning entries in the `entries` array of the round file.
+    pub entries: Vec<usize>,
+
+    /// Points received by each of the authors.
+    pub points: Score,
+}
+
+/// An entry that was disqualified and therefore could not win any category.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Disqualification {
+    /// Position of the round in the manifest.
+    pub round: usize,
+
+    /// Index of the entry in the `entries` array of the round file.
+    pub entry: usize,
+
+    pub author: String,
+
+    pub reason: DisqualificationReason,
+}
+
+/// Why an entry was disqualified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisqualificationReason {
+    /// The contents are longer than the `max_bytes` of the manifest [`Rules`][crate::Rules],
+    /// 1000 bytes by default.
+    TooLong,
+
+    /// The contents have no non-whitespace characters.
+    Empty,
+
+    /// The contents have more characters than the `max_chars` of the manifest rules.
+    TooManyChars,
+
+    /// The contents have fewer words than the `min_words` of the manifest rules.
+    TooFewWords,
+
+    /// The contents have more words than the `max_words` of the manifest rules.
+    TooManyWords,
+
+    /// The contents have more lines than the `max_lines` of the manifest rules.
+    TooManyLines,
+
+    /// The contents contain one of the `banned_words` of the manifest rules.
+    BannedWord,
+
+    /// The contents contain an invalid JSON escape sequence, such as an unpaired `\u` surrogate.
+    ///
+    /// Only solvers that decode the contents themselves (v06 and later) can detect this - earlier
+    /// versions leave decoding to serde_json, which rejects the entire round file instead.
+    InvalidEscape,
+
+    /// The decoded contents are not valid UTF-8.
+    ///
+    /// Only solvers that decode the contents themselves (v06 and later) can detect this.
+    InvalidUtf8,
+
+    /// The contents are some JSON value other than a string.
+    NotAString,
+}
+
+/// Collects the total and the optional parts of a [`ContestResult`] while a solver works
+/// through the rounds.
+///
+/// Recording is skipped entirely for anything not requested in [`SolveOptions`], so solvers
+/// should check the `records_*()` methods before doing any work only needed for recording.
+pub(crate) struct ResultBuilder {
+    /// Index of the current round, `None` until the first round starts.
+    round: Option<usize>,
+
+    /// Sum of all points awarded so far, kept as we go so that we notice as soon as it can no
+    /// longer be represented.
+    total: Score,
+
+    categories: Option<Vec<CategoryInfo>>,
+    rounds: Option<Vec<RoundDetails>>,
+    disqualifications: Option<Vec<Disqualification>>,
+}
+
+impl ResultBuilder {
+    pub(crate) fn new(options: &SolveOptions) -> Self {
+        Self {
+            round: None,
+            total: Score::ZERO,
+            categories: options.round_details.then(Vec::new),
+            rounds: options.round_details.then(Vec::new),
+            disqualifications: options.disqualifications.then(Vec::new),
+        }
+    }
+
+    /// Records the metadata of the next category of the manifest. Does nothing unless requested,
+    /// so it is fine to call this for every category.
+    pub(crate) fn record_category(
+        &mut self,
+        id: Option<&str>,
+        name: Option<&str>,
+        description: Option<&str>,
+    ) {
+        let Some(categories) = &mut self.categories else {
+            return;
+        };
+
+        categories.push(CategoryInfo {
+            index: categories.len(),
+            id: id.map(str::to_owned),
+            name: name.map(str::to_owned),
+            description: description.map(str::to_owned),
+        });
+    }
+
+    /// Starts a new round, to which everything recorded from now on belongs.
+    pub(crate) fn start_round(&mut self, path: &Path) {
+        let index = self.round.map_or(0, |round| round + 1);
+        self.round = Some(index);
+
+        if let Some(rounds) = &mut self.rounds {
+            rounds.push(RoundDetails {
+                index,
+                path: path.to_path_buf(),
+                categories: Vec::new(),
+            });
+        }
+    }
+
+    pub(crate) fn records_winners(&self) -> bool {
+        self.rounds.is_some()
+    }
+
+    /// Records the winners of one place in one category of the current round, where place 0 is
+    /// the best.
+    pub(crate) fn record_winners<A: AsRef<str>>(
+        &mut self,
+        category: usize,
+        place: usize,
+        winners: &Place<A>,
+    ) {
+        let Some(round) = self.rounds.as_mut().and_then(|rounds| rounds.last_mut()) else {
+            return;
+        };
+
+        round.categories.push(CategoryWinners {
+            category,
+            place: place + 1,
+            weight: winners.weight.primary,
+            authors: winners
+                .authors
+                .iter()
+                .map(|a| a.as_ref().to_owned())
+                .collect(),
+            entries: winners.entries(),
+            points: winners.points,
+        });
+    }
+
+    /// Adds points to the score of an author, failing if either that score or the total of all
+    /// scores can no longer be represented exactly.
+    pub(crate) fn award(&mut self, score: &mut Score, points: Score) -> Result<(), RoundError> {
+        let (Some(new_score), Some(new_total)) =
+            (score.checked_add(points), self.total.checked_add(points))
+        else {
+            return Err(RoundError::ScoreOverflow);
+        };
+
+        *score = new_score;
+        self.total = new_total;
+        Ok(())
+    }
+
+    pub(crate) fn records_disqualifications(&self) -> bool {
+        self.disqualifications.is_some()
+    }
+
+    /// Records a disqualified entry of the current round. Does nothing unless requested, so it
+    /// is fine to call this for every disqualified entry.
+    pub(crate) fn record_disqualification(
+        &mut self,
+        entry: usize,
+        author: &str,
+        reason: DisqualificationReason,
+    ) {
+        let (Some(disqualifications), Some(round)) = (&mut self.disqualifications, self.round)
+        else {
+            return;
+        };
+
+        disqualifications.push(Disqualification {
+            round,
+            entry,
+            author: author.to_owned(),
+            reason,
+        });
+    }
+
+    pub(crate) fn finish<A: Into<String>>(
+        self,
+        scores: impl IntoIterator<Item = (A, Score)>,
+    ) -> ContestResult {
+        let mut rounds = self.rounds;
+
+        // Solvers award points in whatever order their maps iterate in, so we sort here.
+        for round in rounds.iter_mut().flatten() {
+            round
+                .categories
+                .sort_by_key(|winners| (winners.category, winners.place));
+        }
+
+        // Solvers may detect different kinds of disqualification in different passes.
+        let mut disqualifications = self.disqualifications;
+
+        if let Some(disqualifications) = &mut disqualifications {
+            disqualifications.sort_by_key(|d| (d.round, d.entry));
+        }
+
+        ContestResult {
+            scores: scores
+                .into_iter()
+                .map(|(author, score)| (author.into(), score))
+                .collect(),
+            total: self.total,
+            categories: self.categories,
+            rounds,
+            disqualifications,
+        }
+    }
+}
+
+/// One line of the leaderboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct LeaderboardEntry<'a> {
+    /// Dense rank of the author, starting from 1 for the highest score.
+    pub rank: usize,
+    pub author: &'a str,
+    pub score: Score,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaderboard_uses_dense_ranking() {
+        let result = ContestResult::from_iter([("d", 1), ("b", 5), ("c", 3), ("a", 5), ("e", 3)]);
+
+        let leaderboard = result
+            .leaderboard()
+            .into_iter()
+            .map(|e| (e.rank, e.author, e.score.numerator()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            leaderboard,
+            [
+                (1, "a", 5),
+                (1, "b", 5),
+                (2, "c", 3),
+                (2, "e", 3),
+                (3, "d", 1)
+            ]
+        );
+        assert_eq!(result.total(), 17);
+        assert_eq!(result.score("c"), 3);
+        assert_eq!(result.score("nobody"), 0);
+    }
+
+    #[test]
+    fn leaderboard_ranks_fractional_scores() {
+        let result = ContestResult::from_iter([
+            ("a", Score::new(1, 2)),
+            ("b", Score::new(4, 3)),
+            ("c", Score::ONE),
+            ("d", Score::new(2, 4)),
+        ]);
+
+        let leaderboard = result
+            .leaderboard()
+            .into_iter()
+            .map(|e| (e.rank, e.author))
+            .collect::<Vec<_>>();
+
+        assert_eq!(leaderboard, [(1, "b"), (2, "c"), (3, "a"), (3, "d")]);
+        assert_eq!(result.total(), Score::new(10, 3));
+    }
+}
@@ -0,0 +1,54 @@
+//! Rules that decide which entries are disqualified, configured in the `rules` section of the
+//! manifest.
+
+use serde::{Deserialize, Serialize};
+
+/// Limits on the contents of entries. Entries that break any of them are disqualified.
+///
+/// Every limit is optional except the length in bytes, which is 1000 bytes unless the manifest
+/// says otherwise, as in the original rules. Regardless of the rules, an entry without words is
+/// always disqualified.
+///
+/// Words and lines are counted as by the weight functions, using the
+/// [`SolveOptions::word_boundary`][crate::SolveOptions::word_boundary] and [`str::lines`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    /// Longest allowed contents, in bytes once decoded.
+    pub max_bytes: usize,
+
+    /// Longest allowed contents, in characters.
+    pub max_chars: Option<usize>,
+
+    /// Fewest words the contents must have.
+    pub min_words: Option<usize>,
+
+    /// Most words the contents may have.
+    pub max_words: Option<usize>,
+
+    /// Most lines the contents may have, including blank ones.
+    pub max_lines: Option<usize>,
+
+    /// Words that must not occur in the contents. They are matched the way keywords are matched
+    /// against the words of a title, so a banned word of more than one word is a phrase, and
+    /// [`SolveOptions::keyword_matching`][crate::SolveOptions::keyword_matching] applies.
+    pub banned_words: Vec<String>,
+}
+
+impl Rules {
+    /// Longest allowed contents by default, in bytes.
+    pub const DEFAULT_MAX_BYTES: usize = 1000;
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            max_bytes: Self::DEFAULT_MAX_BYTES,
+            max_chars: None,
+            min_words: None,
+            max_words: None,
+            max_lines: None,
+            banned_words: Vec::new(),
+        }
+    }
+}
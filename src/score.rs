@@ -0,0 +1,288 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::Sum,
+    num::NonZeroU64,
+    ops::{Add, AddAssign},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, ser::SerializeStruct};
+
+/// Points of an author, or of all authors together.
+///
+/// Scores are whole numbers, unless a tie was settled by splitting the points of a category
+/// between the tied authors (see [`TiePolicy::FractionalSplit`][crate::TiePolicy]). They are
+/// kept as exact fractions, so that they do not depend on the order in which points are added.
+///
+/// Adding scores with `+` panics if the sum cannot be represented exactly, because its numerator
+/// or its denominator does not fit into a `u64`. The solvers use
+/// [`checked_add()`][Self::checked_add] instead and report such a contest as an error.
+///
+/// A score is displayed and serialized as an integer if it is whole. Otherwise it is displayed
+/// as a fraction like `7/3` and serialized as `{ "numerator": 7, "denominator": 3 }`, so that
+/// it deserializes to exactly the same score.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Score {
+    /// Always in lowest terms, with a denominator of at least 1.
+    numerator: u64,
+    denominator: u64,
+}
+
+impl Score {
+    pub const ZERO: Score = Score::whole(0);
+    pub const ONE: Score = Score::whole(1);
+
+    pub const fn whole(points: u64) -> Self {
+        Self {
+            numerator: points,
+            denominator: 1,
+        }
+    }
+
+    /// The fraction `numerator / denominator`.
+    ///
+    /// # Panics
+    ///
+    /// If the denominator is zero.
+    pub fn new(numerator: u64, denominator: u64) -> Self {
+        assert_ne!(denominator, 0, "score with a zero denominator");
+        Self::reduced(numerator.into(), denominator.into())
+            .expect("reducing a fraction of u64 never makes it larger")
+    }
+
+    pub fn numerator(self) -> u64 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> u64 {
+        self.denominator
+    }
+
+    pub fn is_whole(self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// The sum of both scores, `None` if it cannot be represented exactly.
+    pub fn checked_add(self, other: Score) -> Option<Score> {
+        if self.denominator == other.denominator && self.is_whole() {
+            return Some(Score::whole(self.numerator.checked_add(other.numerator)?));
+        }
+
+        let numerator = u128::from(self.numerator) * u128::from(other.denominator)
+            + u128::from(other.numerator) * u128::from(self.denominator);
+        let denominator = u128::from(self.denominator) * u128::from(other.denominator);
+
+        Score::reduced(numerator, denominator)
+    }
+
+    fn reduced(numerator: u128, denominator: u128) -> Option<Self> {
+        let divisor = gcd(numerator, denominator);
+
+        Some(Self {
+            numerator: u64::try_from(numerator / divisor).ok()?,
+            denominator: u64::try_from(denominator / divisor).ok()?,
+        })
+    }
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl From<u64> for Score {
+    fn from(points: u64) -> Self {
+        Self::whole(points)
+    }
+}
+
+impl Add for Score {
+    type Output = Score;
+
+    fn add(self, other: Score) -> Score {
+        self.checked_add(other).expect("score overflow")
+    }
+}
+
+impl AddAssign for Score {
+    fn add_assign(&mut self, other: Score) {
+        *self = *self + other;
+    }
+}
+
+impl Sum for Score {
+    fn sum<I: Iterator<Item = Score>>(iter: I) -> Score {
+        iter.fold(Score::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Score> for Score {
+    fn sum<I: Iterator<Item = &'a Score>>(iter: I) -> Score {
+        iter.copied().sum()
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Score) -> Ordering {
+        let left = u128::from(self.numerator) * u128::from(other.denominator);
+        let right = u128::from(other.numerator) * u128::from(self.denominator);
+
+        left.cmp(&right)
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Score) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq<u64> for Score {
+    fn eq(&self, other: &u64) -> bool {
+        *self == Score::whole(*other)
+    }
+}
+
+impl PartialEq<Score> for u64 {
+    fn eq(&self, other: &Score) -> bool {
+        Score::whole(*self) == *other
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_whole() {
+            fmt::Display::fmt(&self.numerator, f)
+        } else {
+            f.pad(&format!("{}/{}", self.numerator, self.denominator))
+        }
+    }
+}
+
+impl Serialize for Score {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.is_whole() {
+            serializer.serialize_u64(self.numerator)
+        } else {
+            let mut fraction = serializer.serialize_struct("Score", 2)?;
+            fraction.serialize_field("numerator", &self.numerator)?;
+            fraction.serialize_field("denominator", &self.denominator)?;
+            fraction.end()
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Score {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Serialized {
+            Whole(u64),
+            Fraction {
+                numerator: u64,
+                denominator: NonZeroU64,
+            },
+        }
+
+        // Fractions need not be in lowest terms, they are reduced like any other.
+        Ok(match Serialized::deserialize(deserializer)? {
+            Serialized::Whole(points) => Score::whole(points),
+            Serialized::Fraction {
+                numerator,
+                denominator,
+            } => Score::new(numerator, denominator.get()),
+        })
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractions_are_exact_and_reduced() {
+        let third = Score::new(1, 3);
+        let sum = third + third + third;
+
+        assert_eq!(sum, Score::ONE);
+        assert!(sum.is_whole());
+        assert_eq!(Score::new(2, 4), Score::new(1, 2));
+        assert_eq!(Score::new(1, 2) + Score::new(1, 3), Score::new(5, 6));
+        assert_eq!(
+            [Score::new(1, 2), Score::whole(2)].iter().sum::<Score>(),
+            Score::new(5, 2)
+        );
+    }
+
+    #[test]
+    fn overflow_is_detected() {
+        assert_eq!(Score::whole(u64::MAX).checked_add(Score::ONE), None);
+        assert_eq!(
+            Score::whole(u64::MAX - 1).checked_add(Score::ONE),
+            Some(Score::whole(u64::MAX))
+        );
+
+        // The denominators of 1/2 + 1/3 + ... + 1/53 multiply to more than u64::MAX.
+        let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+        let sum = primes.iter().try_fold(Score::ZERO, |sum, &prime| {
+            sum.checked_add(Score::new(1, prime))
+        });
+
+        assert_eq!(sum, None);
+    }
+
+    #[test]
+    fn scores_are_ordered_by_value() {
+        assert!(Score::new(1, 3) < Score::new(1, 2));
+        assert!(Score::new(5, 2) > Score::whole(2));
+        assert_eq!(Score::new(6, 3).cmp(&Score::whole(2)), Ordering::Equal);
+    }
+
+    #[test]
+    fn whole_scores_look_like_integers() {
+        assert_eq!(Score::whole(3), 3);
+        assert_eq!(format!("{:>4}", Score::whole(3)), "   3");
+        assert_eq!(format!("{:>4}", Score::new(7, 3)), " 7/3");
+        assert_eq!(serde_json::to_string(&Score::whole(3)).unwrap(), "3");
+        assert_eq!(
+            serde_json::to_string(&Score::new(1, 2)).unwrap(),
+            r#"{"numerator":1,"denominator":2}"#
+        );
+    }
+
+    #[test]
+    fn serialized_scores_round_trip_exactly() {
+        for score in [
+            Score::ZERO,
+            Score::whole(u64::MAX),
+            Score::new(7, 3),
+            Score::new(u64::MAX - 1, u64::MAX),
+        ] {
+            let json = serde_json::to_string(&score).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Score>(&json).unwrap(),
+                score,
+                "{json}"
+            );
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Score>(r#"{ "numerator": 4, "denominator": 6 }"#).unwrap(),
+            Score::new(2, 3)
+        );
+        assert!(serde_json::from_str::<Score>(r#"{ "numerator": 1, "denominator": 0 }"#).is_err());
+    }
+}
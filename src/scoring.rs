@@ -0,0 +1,457 @@
+//! Building blocks shared by the solvers for tracking the winners of each category.
+
+use std::{cmp::Ordering, fmt, num::NonZeroU64};
+
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, SeqAccess, Visitor},
+};
+
+use crate::{Score, Weight};
+
+/// How a category is awarded if more than one author has an entry with the highest weight,
+/// selected by the `tie_policy` field of the manifest.
+///
+/// An author with more than one entry of the highest weight is never tied with themselves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TiePolicy {
+    /// Every tied author receives the full point, as in the original rules.
+    #[default]
+    AllTied,
+
+    /// Only the author of the tied entry that comes first in the round file receives the point.
+    EarliestEntry,
+
+    /// The point is split evenly between the tied authors, so scores may become fractions.
+    FractionalSplit,
+
+    /// Nobody receives the point.
+    NoAward,
+
+    /// The tied entries are compared by another weight, given in the manifest like
+    /// `{ "secondary_weight": "char_density" }`. If they are still tied, every author still
+    /// tied receives the full point.
+    SecondaryWeight(Weight),
+}
+
+/// Deserializes the podium of a manifest, the points of each place, rejecting one without any
+/// places.
+pub(crate) fn deserialize_podium<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<NonZeroU64>>, D::Error> {
+    // Rejecting the podium while its places are visited, rather than once it has been
+    // deserialized, makes serde_json report the position of the podium itself.
+    struct PodiumVisitor;
+
+    impl<'de> Visitor<'de> for PodiumVisitor {
+        type Value = Vec<NonZeroU64>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a podium of at least one place")
+        }
+
+        fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+            let mut podium = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+            while let Some(points) = seq.next_element()? {
+                podium.push(points);
+            }
+
+            if podium.is_empty() {
+                return Err(de::Error::invalid_length(0, &self));
+            }
+
+            Ok(podium)
+        }
+    }
+
+    struct Podium(Vec<NonZeroU64>);
+
+    impl<'de> Deserialize<'de> for Podium {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_seq(PodiumVisitor).map(Self)
+        }
+    }
+
+    Ok(Option::<Podium>::deserialize(deserializer)?.map(|podium| podium.0))
+}
+
+/// The weight of an entry, with the secondary weight that breaks ties if the [`TiePolicy`]
+/// asks for one. Entries are ranked by their primary weight first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub(crate) struct EntryWeight {
+    pub(crate) primary: f64,
+
+    /// Always zero unless the tie policy is [`TiePolicy::SecondaryWeight`].
+    pub(crate) secondary: f64,
+}
+
+/// The best entries found so far in one category of one round, grouped into places by their
+/// weight. Only the best few distinct weights are kept, as many as there are places on the
+/// podium, so that an entry that can no longer win anything is forgotten right away.
+pub(crate) struct CategoryPodium<A> {
+    /// Ordered from the highest weight to the lowest, at most `capacity` of them.
+    places: Vec<Place<A>>,
+    capacity: usize,
+}
+
+/// The entries that share one weight in one category of one round.
+pub(crate) struct Place<A> {
+    pub(crate) weight: EntryWeight,
+
+    /// Authors of the entries with this weight, each listed once, in the order of their first
+    /// entry with this weight.
+    pub(crate) authors: Vec<A>,
+
+    /// Indices of the entries with this weight, with the index of their author in `authors`.
+    /// Only collected if the caller wants to know the winning entries, otherwise always empty.
+    entries: Vec<(usize, usize)>,
+
+    /// Points received by each of the authors, once the place has been awarded.
+    pub(crate) points: Score,
+}
+
+impl<A: PartialEq> CategoryPodium<A> {
+    /// An empty podium with the given number of places.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            places: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Considers an entry that matched the category. Entries must be offered in the order they
+    /// appear in the round file.
+    pub(crate) fn offer(
+        &mut self,
+        weight: EntryWeight,
+        author: A,
+        entry: usize,
+        record_entries: bool,
+    ) {
+        let mut index = 0;
+
+        for place in &mut self.places {
+            match weight.partial_cmp(&place.weight) {
+                Some(Ordering::Greater) => break,
+                Some(Ordering::Equal) => {
+                    // Tie with the entries of this place.
+                    place.add(author, entry, record_entries);
+                    return;
+                }
+                Some(Ordering::Less) => index += 1,
+                None => return,
+            }
+        }
+
+        if index >= self.capacity {
+            // Too low for the podium.
+            return;
+        }
+
+        // A new place, pushing the lowest one off the podium if it is full. We reuse the
+        // allocations of the place that falls off.
+        let mut place = if self.places.len() == self.capacity {
+            self.places.pop().expect("a full podium has places")
+        } else {
+            Place {
+                weight,
+                authors: Vec::new(),
+                entries: Vec::new(),
+                points: Score::ZERO,
+            }
+        };
+
+        place.weight = weight;
+        place.authors.clear();
+        place.entries.clear();
+        place.add(author, entry, record_entries);
+
+        self.places.insert(index, place);
+    }
+
+    /// Settles the places after all entries have been offered, given the points that each place
+    /// is worth. An author only receives the points of their best place, so they are removed
+    /// from the places below it, and ties are settled according to the policy.
+    ///
+    /// Returns the places from best to worst, with the points that each of their remaining
+    /// authors receives. Places may be left without authors.
+    pub(crate) fn award(
+        mut self,
+        tie_policy: TiePolicy,
+        points: impl Fn(usize) -> u64,
+    ) -> Vec<Place<A>> {
+        for index in 0..self.places.len() {
+            let (better, rest) = self.places.split_at_mut(index);
+            let place = &mut rest[0];
+
+            if !better.is_empty() {
+                place.retain_authors(|author| {
+                    !better.iter().any(|better| better.authors.contains(author))
+                });
+            }
+
+            place.settle(tie_policy, points(index));
+        }
+
+        self.places
+    }
+}
+
+impl<A> Place<A> {
+    /// Indices of the entries of the remaining authors, if the caller wanted to know them.
+    pub(crate) fn entries(&self) -> Vec<usize> {
+        self.entries.iter().map(|&(entry, _)| entry).collect()
+    }
+}
+
+impl<A: PartialEq> Place<A> {
+    fn add(&mut self, author: A, entry: usize, record_entries: bool) {
+        // Add author if not already present.
+        let author_idx = match self.authors.iter().position(|a| *a == author) {
+            Some(author_idx) => author_idx,
+            None => {
+                self.authors.push(author);
+                self.authors.len() - 1
+            }
+        };
+
+        if record_entries {
+            self.entries.push((entry, author_idx));
+        }
+    }
+
+    fn retain_authors(&mut self, mut keep: impl FnMut(&A) -> bool) {
+        // New index of each author, `None` if removed.
+        let mut new_indices = Vec::with_capacity(self.authors.len());
+        let mut kept = 0;
+
+        for author in &self.authors {
+            if keep(author) {
+                new_indices.push(Some(kept));
+                kept += 1;
+            } else {
+                new_indices.push(None);
+            }
+        }
+
+        let mut new_index = new_indices.iter();
+        self.authors
+            .retain(|_| new_index.next().is_some_and(Option::is_some));
+
+        self.entries
+            .retain_mut(|(_, author_idx)| match new_indices[*author_idx] {
+                Some(new_idx) => {
+                    *author_idx = new_idx;
+                    true
+                }
+                None => false,
+            });
+    }
+
+    /// Settles a tie according to the policy, given the points that the place is worth.
+    fn settle(&mut self, tie_policy: TiePolicy, points: u64) {
+        self.points = Score::whole(points);
+
+        if self.authors.len() <= 1 {
+            return;
+        }
+
+        match tie_policy {
+            TiePolicy::EarliestEntry => {
+                // Authors are listed in the order of their first entry, which comes first.
+                self.authors.truncate(1);
+                self.entries.truncate(1);
+            }
+            TiePolicy::FractionalSplit => {
+                self.points = Score::new(points, self.authors.len() as u64);
+            }
+            TiePolicy::NoAward => {
+                self.authors.clear();
+                self.entries.clear();
+                self.points = Score::ZERO;
+            }
+            TiePolicy::AllTied | TiePolicy::SecondaryWeight(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight(primary: f64, secondary: f64) -> EntryWeight {
+        EntryWeight { primary, secondary }
+    }
+
+    fn podium(
+        points: &[u64],
+        tie_policy: TiePolicy,
+        entries: &[(EntryWeight, &'static str)],
+    ) -> Vec<(Vec<&'static str>, Vec<usize>, Score)> {
+        let mut podium = CategoryPodium::new(points.len());
+
+        for (index, &(weight, author)) in entries.iter().enumerate() {
+            podium.offer(weight, author, index, true);
+        }
+
+        podium
+            .award(tie_policy, |place| points[place])
+            .into_iter()
+            .map(|place| {
+                let entries = place.entries();
+                (place.authors, entries, place.points)
+            })
+            .collect()
+    }
+
+    fn best(
+        tie_policy: TiePolicy,
+        entries: &[(EntryWeight, &'static str)],
+    ) -> (Vec<&'static str>, Vec<usize>, Score) {
+        let mut places = podium(&[1], tie_policy, entries);
+        assert_eq!(places.len(), 1);
+
+        places.pop().unwrap()
+    }
+
+    #[test]
+    fn ties_are_settled_by_policy() {
+        let entries = [
+            (weight(1.0, 0.0), "A"),
+            (weight(2.0, 0.0), "B"),
+            (weight(2.0, 0.0), "C"),
+            (weight(2.0, 0.0), "B"),
+            (weight(2.0, 0.0), "D"),
+        ];
+
+        assert_eq!(
+            best(TiePolicy::AllTied, &entries),
+            (vec!["B", "C", "D"], vec![1, 2, 3, 4], Score::ONE)
+        );
+        assert_eq!(
+            best(TiePolicy::EarliestEntry, &entries),
+            (vec!["B"], vec![1], Score::ONE)
+        );
+        assert_eq!(
+            best(TiePolicy::FractionalSplit, &entries),
+            (vec!["B", "C", "D"], vec![1, 2, 3, 4], Score::new(1, 3))
+        );
+        assert_eq!(
+            best(TiePolicy::NoAward, &entries),
+            (vec![], vec![], Score::ZERO)
+        );
+    }
+
+    #[test]
+    fn points_are_split_between_tied_authors() {
+        let entries = [(weight(2.0, 0.0), "A"), (weight(2.0, 0.0), "B")];
+
+        assert_eq!(
+            podium(&[3], TiePolicy::FractionalSplit, &entries),
+            [(vec!["A", "B"], vec![0, 1], Score::new(3, 2))]
+        );
+    }
+
+    #[test]
+    fn podium_keeps_best_distinct_weights() {
+        let entries = [
+            (weight(1.0, 0.0), "A"),
+            (weight(4.0, 0.0), "B"),
+            (weight(2.0, 0.0), "C"),
+            (weight(3.0, 0.0), "D"),
+            (weight(2.0, 0.0), "E"),
+            (weight(0.5, 0.0), "F"),
+        ];
+
+        assert_eq!(
+            podium(&[3, 2, 1], TiePolicy::AllTied, &entries),
+            [
+                (vec!["B"], vec![1], Score::whole(3)),
+                (vec!["D"], vec![3], Score::whole(2)),
+                (vec!["C", "E"], vec![2, 4], Score::ONE),
+            ]
+        );
+        assert_eq!(
+            podium(&[3, 2, 1], TiePolicy::FractionalSplit, &entries[..3]),
+            [
+                (vec!["B"], vec![1], Score::whole(3)),
+                (vec!["C"], vec![2], Score::whole(2)),
+                (vec!["A"], vec![0], Score::ONE),
+            ]
+        );
+    }
+
+    #[test]
+    fn authors_only_receive_their_best_place() {
+        let entries = [
+            (weight(3.0, 0.0), "A"),
+            (weight(2.0, 0.0), "B"),
+            (weight(2.0, 0.0), "A"),
+            (weight(1.0, 0.0), "A"),
+            (weight(1.0, 0.0), "C"),
+        ];
+
+        // A is no longer tied with B for second place, so B gets all of its points.
+        assert_eq!(
+            podium(&[3, 2, 1], TiePolicy::NoAward, &entries),
+            [
+                (vec!["A"], vec![0], Score::whole(3)),
+                (vec!["B"], vec![1], Score::whole(2)),
+                (vec!["C"], vec![4], Score::ONE),
+            ]
+        );
+
+        // Nobody wins the tied first place, so A can still win the second.
+        let entries = [
+            (weight(3.0, 0.0), "A"),
+            (weight(3.0, 0.0), "B"),
+            (weight(2.0, 0.0), "A"),
+        ];
+
+        assert_eq!(
+            podium(&[3, 2], TiePolicy::NoAward, &entries),
+            [
+                (vec![], vec![], Score::ZERO),
+                (vec!["A"], vec![2], Score::whole(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn author_is_not_tied_with_themselves() {
+        let entries = [(weight(2.0, 0.0), "A"), (weight(2.0, 0.0), "A")];
+
+        assert_eq!(
+            best(TiePolicy::NoAward, &entries),
+            (vec!["A"], vec![0, 1], Score::ONE)
+        );
+    }
+
+    #[test]
+    fn secondary_weight_breaks_ties() {
+        let policy = TiePolicy::SecondaryWeight(Weight::CharDensity);
+        let entries = [
+            (weight(2.0, 1.0), "A"),
+            (weight(2.0, 3.0), "B"),
+            (weight(1.0, 9.0), "C"),
+            (weight(2.0, 3.0), "D"),
+        ];
+
+        assert_eq!(
+            best(policy, &entries),
+            (vec!["B", "D"], vec![1, 3], Score::ONE)
+        );
+    }
+
+    #[test]
+    fn tie_policy_is_deserialized_by_name() {
+        let policy: TiePolicy = serde_json::from_str(r#""fractional_split""#).unwrap();
+        assert_eq!(policy, TiePolicy::FractionalSplit);
+
+        let policy: TiePolicy =
+            serde_json::from_str(r#"{ "secondary_weight": "line_count" }"#).unwrap();
+        assert_eq!(policy, TiePolicy::SecondaryWeight(Weight::LineCount));
+    }
+}
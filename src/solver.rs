@@ -0,0 +1,1189 @@
+//! Common interface implemented by every version of the solver, so that benchmarks, tests and
+//! tools can work with all of them without hard-coding each one.
+
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use crate::{
+    ContestError, ContestResult, SolveOptions, v01_simple, v02_less_cloning, v03_borrow_document,
+    v04_borrow_more, v05_reserve_and_reuse, v06_raw_contents, v07_reuse_more, v08_faster_maps,
+};
+
+/// Loads the JSON of one round, given the round path exactly as listed in the manifest.
+///
+/// The loader appends the round JSON to the provided buffer. Solvers that reuse one buffer for
+/// all rounds clear it before each call, so the loader does not need to.
+pub type RoundLoader<'a> = dyn FnMut(&Path, &mut String) -> io::Result<()> + 'a;
+
+/// One version of the poetry contest solver.
+///
+/// All versions implement the same scoring rules (see [`v01_simple::solve()`]) and must produce
+/// the same results - they only differ in how efficiently they do it.
+pub trait Solver: Sync {
+    /// Short unique name of the solver, identical to the name of the module that contains it.
+    fn name(&self) -> &'static str;
+
+    /// One sentence describing what this version does differently from the previous one.
+    fn description(&self) -> &'static str;
+
+    /// Solves the data set whose `manifest.json` is located in `data_dir`.
+    fn solve_dir(&self, data_dir: &Path) -> Result<ContestResult, ContestError> {
+        self.solve_manifest(&data_dir.join("manifest.json"))
+    }
+
+    /// Solves the data set described by the manifest at `manifest_path`, resolving round paths
+    /// relative to the directory that contains the manifest.
+    fn solve_manifest(&self, manifest_path: &Path) -> Result<ContestResult, ContestError> {
+        self.solve_manifest_with(manifest_path, &SolveOptions::default())
+    }
+
+    /// Same as [`solve_manifest()`][Self::solve_manifest], additionally recording whatever the
+    /// options request.
+    fn solve_manifest_with(
+        &self,
+        manifest_path: &Path,
+        options: &SolveOptions,
+    ) -> Result<ContestResult, ContestError>;
+
+    /// Solves a data set whose manifest has already been loaded, using the provided loader to
+    /// obtain the JSON of each round referenced by the manifest.
+    ///
+    /// This allows data sets to be solved without them being stored on the filesystem.
+    fn solve_str(
+        &self,
+        manifest_json: &str,
+        load_round: &mut RoundLoader<'_>,
+    ) -> Result<ContestResult, ContestError> {
+        self.solve_str_with(manifest_json, load_round, &SolveOptions::default())
+    }
+
+    /// Same as [`solve_str()`][Self::solve_str], additionally recording whatever the options
+    /// request.
+    fn solve_str_with(
+        &self,
+        manifest_json: &str,
+        load_round: &mut RoundLoader<'_>,
+        options: &SolveOptions,
+    ) -> Result<ContestResult, ContestError>;
+}
+
+static SOLVERS: [&dyn Solver; 8] = [
+    &v01_simple::Simple,
+    &v02_less_cloning::LessCloning,
+    &v03_borrow_document::BorrowDocument,
+    &v04_borrow_more::BorrowMore,
+    &v05_reserve_and_reuse::ReserveAndReuse,
+    &v06_raw_contents::RawContents,
+    &v07_reuse_more::ReuseMore,
+    &v08_faster_maps::FasterMaps,
+];
+
+/// Returns every version of the solver, ordered from the simplest to the most optimized.
+pub fn solvers() -> &'static [&'static dyn Solver] {
+    &SOLVERS
+}
+
+/// Finds a solver by its full name (e.g. `v03_borrow_document`) or just its version (`v03`).
+pub fn find_solver(name: &str) -> Option<&'static dyn Solver> {
+    solvers().iter().copied().find(|solver| {
+        solver.name() == name
+            || solver
+                .name()
+                .split_once('_')
+                .is_some_and(|(version, _)| version == name)
+    })
+}
+
+/// Reads the round file at `round_path` (relative to `data_dir`), appending it to `round_json`.
+pub(crate) fn read_round_file(
+    data_dir: &Path,
+    round_path: &Path,
+    round_json: &mut String,
+) -> io::Result<()> {
+    File::open(data_dir.join(round_path))?.read_to_string(round_json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, env, fs, path::Path, process, sync::Arc};
+
+    use super::*;
+    use crate::{
+        CategoryInfo, CategoryWinners, DisqualificationReason, KeywordMatching, NormalizationForm,
+        Punctuation, RoundDetails, Score, WeightFunction, WordBoundary,
+    };
+
+    const MANIFEST_JSON: &str = r#"{
+        "categories": [
+            { "keywords": ["rose", "red"] },
+            { "keywords": ["blue"] }
+        ],
+        "rounds": ["round_0.json"]
+    }"#;
+
+    const ROUND_JSON: &str = r#"{
+        "entries": [
+            { "author": "A", "title": "a red rose", "contents": "aaaa bb" },
+            { "author": "B", "title": "blue sky", "contents": "cccccc" },
+            { "author": "C", "title": "blue red", "contents": "dd ee" },
+            { "author": "D", "title": "rose", "contents": "   \n " },
+            { "author": "E", "title": "rose", "contents": "ffff\ngg" }
+        ]
+    }"#;
+
+    fn load_round(round_path: &Path, round_json: &mut String) -> io::Result<()> {
+        assert_eq!(round_path, Path::new("round_0.json"));
+        round_json.push_str(ROUND_JSON);
+        Ok(())
+    }
+
+    /// Solves a contest whose rounds are `round_0.json`, `round_1.json` and so on with every
+    /// solver, checking that they all agree. Returns the result of each solver by name.
+    fn solve_all_rounds(
+        manifest_json: &str,
+        rounds: &[&str],
+        options: &SolveOptions,
+    ) -> Vec<(&'static str, ContestResult)> {
+        let mut load_round = |path: &Path, buffer: &mut String| {
+            let index = path
+                .to_str()
+                .and_then(|path| path.strip_prefix("round_")?.strip_suffix(".json"))
+                .and_then(|index| index.parse::<usize>().ok())
+                .filter(|&index| index < rounds.len())
+                .ok_or(io::ErrorKind::NotFound)?;
+
+            buffer.push_str(rounds[index]);
+            Ok(())
+        };
+
+        let results = solvers()
+            .iter()
+            .map(|solver| {
+                let result = solver
+                    .solve_str_with(manifest_json, &mut load_round, options)
+                    .unwrap_or_else(|e| panic!("{}: {e}", solver.name()));
+
+                (solver.name(), result)
+            })
+            .collect::<Vec<_>>();
+
+        let (first_solver, first_result) = &results[0];
+
+        for (solver, result) in &results[1..] {
+            assert_eq!(
+                result, first_result,
+                "{solver} disagrees with {first_solver}"
+            );
+        }
+
+        results
+    }
+
+    /// Same as [`solve_all_rounds()`] for a contest of a single round.
+    fn solve_all(
+        manifest_json: &str,
+        round_json: &str,
+        options: &SolveOptions,
+    ) -> Vec<(&'static str, ContestResult)> {
+        solve_all_rounds(manifest_json, &[round_json], options)
+    }
+
+    /// Solves a contest of a single round with every solver, expecting all of them to fail.
+    /// Returns the error of each solver by name.
+    fn solve_all_err(manifest_json: &str, round_json: &str) -> Vec<(&'static str, ContestError)> {
+        solvers()
+            .iter()
+            .map(|solver| {
+                let error = solver
+                    .solve_str(manifest_json, &mut |_, buffer| {
+                        buffer.push_str(round_json);
+                        Ok(())
+                    })
+                    .expect_err(solver.name());
+
+                (solver.name(), error)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn names_are_unique() {
+        let names = solvers().iter().map(|s| s.name()).collect::<HashSet<_>>();
+        assert_eq!(names.len(), solvers().len());
+    }
+
+    #[test]
+    fn find_solver_by_name_or_version() {
+        assert_eq!(find_solver("v03").unwrap().name(), "v03_borrow_document");
+        assert_eq!(
+            find_solver("v08_faster_maps").unwrap().name(),
+            "v08_faster_maps"
+        );
+        assert!(find_solver("v09").is_none());
+        assert!(find_solver("v03_borrow").is_none());
+    }
+
+    #[test]
+    fn all_solvers_solve_in_memory_data_set() {
+        for solver in solvers() {
+            // Category 0: A and E tie with weight 3.5, category 1: B wins with weight 6.
+            // D is disqualified because it has no non-whitespace contents.
+            let result = solver.solve_str(MANIFEST_JSON, &mut load_round).unwrap();
+            assert_eq!(
+                result,
+                ContestResult::from_iter([("A", 1), ("B", 1), ("E", 1)]),
+                "{}",
+                solver.name()
+            );
+        }
+    }
+
+    #[test]
+    fn all_solvers_record_round_details() {
+        let options = SolveOptions {
+            round_details: true,
+            ..Default::default()
+        };
+
+        for (solver, result) in solve_all(MANIFEST_JSON, ROUND_JSON, &options) {
+            assert_eq!(
+                result.rounds().unwrap(),
+                [RoundDetails {
+                    index: 0,
+                    path: "round_0.json".into(),
+                    categories: vec![
+                        CategoryWinners {
+                            category: 0,
+                            place: 1,
+                            weight: 3.5,
+                            authors: vec!["A".to_owned(), "E".to_owned()],
+                            entries: vec![0, 4],
+                            points: Score::ONE,
+                        },
+                        CategoryWinners {
+                            category: 1,
+                            place: 1,
+                            weight: 6.0,
+                            authors: vec!["B".to_owned()],
+                            entries: vec![1],
+                            points: Score::ONE,
+                        },
+                    ],
+                }],
+                "{solver}"
+            );
+        }
+    }
+
+    #[test]
+    fn round_details_are_not_recorded_by_default() {
+        for solver in solvers() {
+            let result = solver.solve_str(MANIFEST_JSON, &mut load_round).unwrap();
+            assert!(result.rounds().is_none(), "{}", solver.name());
+            assert!(result.categories().is_none(), "{}", solver.name());
+        }
+    }
+
+    #[test]
+    fn all_solvers_record_category_metadata() {
+        let manifest_json = r#"{
+            "categories": [
+                {
+                    "id": "roses",
+                    "name": "Roses",
+                    "description": "Poems about \"roses\" \u2013 red or not",
+                    "keywords": ["rose", "red"]
+                },
+                { "keywords": ["blue"], "name": "Blues" },
+                { "keywords": ["green"] }
+            ],
+            "rounds": ["round_0.json"]
+        }"#;
+
+        let options = SolveOptions {
+            round_details: true,
+            ..Default::default()
+        };
+
+        for (solver, result) in solve_all(manifest_json, ROUND_JSON, &options) {
+            assert_eq!(
+                result.categories().unwrap(),
+                [
+                    CategoryInfo {
+                        index: 0,
+                        id: Some("roses".to_owned()),
+                        name: Some("Roses".to_owned()),
+                        description: Some("Poems about \"roses\" \u{2013} red or not".to_owned()),
+                    },
+                    CategoryInfo {
+                        index: 1,
+                        id: None,
+                        name: Some("Blues".to_owned()),
+                        description: None,
+                    },
+                    CategoryInfo {
+                        index: 2,
+                        id: None,
+                        name: None,
+                        description: None,
+                    },
+                ],
+                "{solver}"
+            );
+
+            let labels = result
+                .categories()
+                .unwrap()
+                .iter()
+                .map(|category| category.label())
+                .collect::<Vec<_>>();
+
+            assert_eq!(labels, ["Roses", "Blues", "#2"], "{solver}");
+        }
+    }
+
+    #[test]
+    fn all_solvers_record_disqualifications() {
+        let round_json = format!(
+            r#"{{
+                "entries": [
+                    {{ "author": "A", "title": "rose", "contents": "{}" }},
+                    {{ "author": "B", "title": "rose", "contents": 42 }},
+                    {{ "author": "C", "title": "no category", "contents": " \n " }},
+                    {{ "author": "D", "title": "rose", "contents": "ok" }},
+                    {{ "author": "E", "title": "blue", "contents": {{ "nested": ["x"] }} }}
+                ]
+            }}"#,
+            "x".repeat(1001)
+        );
+
+        let options = SolveOptions {
+            disqualifications: true,
+            ..Default::default()
+        };
+
+        for (solver, result) in solve_all(MANIFEST_JSON, &round_json, &options) {
+            let disqualifications = result
+                .disqualifications()
+                .unwrap()
+                .iter()
+                .map(|d| (d.round, d.entry, d.author.as_str(), d.reason))
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                disqualifications,
+                [
+                    (0, 0, "A", DisqualificationReason::TooLong),
+                    (0, 1, "B", DisqualificationReason::NotAString),
+                    (0, 2, "C", DisqualificationReason::Empty),
+                    (0, 4, "E", DisqualificationReason::NotAString),
+                ],
+                "{solver}"
+            );
+            assert_eq!(result.score("D"), 1, "{solver}");
+            assert_eq!(result.total(), 1, "{solver}");
+        }
+    }
+
+    #[test]
+    fn disqualified_entry_does_not_pass_its_categories_on() {
+        // A is disqualified in a category, B is in no category and must not inherit it.
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "rose", "contents": " \n " },
+                { "author": "B", "title": "no category", "contents": "bbb" }
+            ]
+        }"#;
+
+        for (solver, result) in solve_all(MANIFEST_JSON, round_json, &SolveOptions::default()) {
+            assert_eq!(result, ContestResult::default(), "{solver}");
+        }
+    }
+
+    #[test]
+    fn all_solvers_apply_manifest_rules() {
+        let manifest_json = r#"{
+            "categories": [{ "keywords": ["rose"] }],
+            "rounds": ["round_0.json"],
+            "rules": {
+                "max_bytes": 30,
+                "max_chars": 10,
+                "min_words": 2,
+                "max_words": 4,
+                "max_lines": 2,
+                "banned_words": ["thorn", "red petal"]
+            }
+        }"#;
+
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "rose", "contents": "\u00e9\u00e9\u00e9\u00e9\u00e9\u00e9\u00e9\u00e9 \u00e9\u00e9" },
+                { "author": "B", "title": "rose", "contents": "xxxxxxxxxxxxxxx xxxxxxxxxxxxxxx" },
+                { "author": "C", "title": "no category", "contents": "single" },
+                { "author": "D", "title": "rose", "contents": "a b c d e" },
+                { "author": "E", "title": "rose", "contents": "a b\nc\nd" },
+                { "author": "F", "title": "rose", "contents": "a thorn" },
+                { "author": "G", "title": "rose", "contents": "red petal" },
+                { "author": "H", "title": "rose", "contents": "petal red" },
+                { "author": "I", "title": "rose", "contents": "thorny x" }
+            ]
+        }"#;
+
+        let options = SolveOptions {
+            disqualifications: true,
+            ..Default::default()
+        };
+
+        for (solver, result) in solve_all(manifest_json, round_json, &options) {
+            let disqualifications = result
+                .disqualifications()
+                .unwrap()
+                .iter()
+                .map(|d| (d.author.as_str(), d.reason))
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                disqualifications,
+                [
+                    ("A", DisqualificationReason::TooManyChars),
+                    ("B", DisqualificationReason::TooLong),
+                    ("C", DisqualificationReason::TooFewWords),
+                    ("D", DisqualificationReason::TooManyWords),
+                    ("E", DisqualificationReason::TooManyLines),
+                    ("F", DisqualificationReason::BannedWord),
+                    ("G", DisqualificationReason::BannedWord),
+                ],
+                "{solver}"
+            );
+            assert_eq!(result.score("H"), 1, "{solver}");
+            assert_eq!(result.total(), 1, "{solver}");
+        }
+    }
+
+    #[test]
+    fn word_boundary_changes_results_on_non_ascii_poems() {
+        let manifest_json = r#"{
+            "categories": [
+                { "keywords": ["moon"] },
+                { "keywords": ["池"] }
+            ],
+            "rounds": ["round_0.json"]
+        }"#;
+
+        // A has a no-break space in both title and contents, B and C are written without spaces,
+        // D has no letters at all and E separates its words with ideographic spaces.
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "moon\u00a0rise", "contents": "lune\u00a0pleine" },
+                { "author": "B", "title": "moon", "contents": "古池や蛙飛び込む" },
+                { "author": "C", "title": "古池", "contents": "水の音" },
+                { "author": "D", "title": "池", "contents": "— ‽ —" },
+                { "author": "E", "title": "moon", "contents": "星\u3000星\u3000星\u3000星\u3000星" }
+            ]
+        }"#;
+
+        let expected = [
+            // moon: A 6, B 24, E 5.4. 池: D 11/3, C does not match.
+            (WordBoundary::UnicodeWhitespace, ["B", "D"]),
+            // moon: B 24, E 27, A does not match. 池: D 11/3, C does not match.
+            (WordBoundary::AsciiWhitespace, ["E", "D"]),
+            // moon: A 6, B 3, E 5.4. 池: C 3, D is disqualified as it has no words.
+            (WordBoundary::UnicodeWords, ["A", "C"]),
+        ];
+
+        for (word_boundary, winners) in expected {
+            let options = SolveOptions {
+                word_boundary,
+                ..Default::default()
+            };
+
+            for (solver, result) in solve_all(manifest_json, round_json, &options) {
+                assert_eq!(
+                    result,
+                    ContestResult::from_iter(winners.map(|author| (author, 1))),
+                    "{solver} {word_boundary:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn keyword_matching_folds_case_and_normalizes() {
+        // The keywords are converted as well, "Été" is uppercase and precomposed.
+        let manifest_json = r#"{
+            "categories": [
+                { "keywords": ["love"] },
+                { "keywords": ["\u00c9t\u00e9"] },
+                { "keywords": ["hello"] }
+            ],
+            "rounds": ["round_0.json"]
+        }"#;
+
+        // B uses a combining acute accent, C a full-width letter. D and E start with letters that
+        // compatibility normalization turns into uppercase ones, "ℌ" and a mathematical bold "L".
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "Love song", "contents": "aa" },
+                { "author": "B", "title": "e\u0301te\u0301", "contents": "bbb" },
+                { "author": "C", "title": "\uff4cove", "contents": "cccc" },
+                { "author": "D", "title": "\u210cello", "contents": "dd" },
+                { "author": "E", "title": "\ud835\udc0bove", "contents": "eeeee" }
+            ]
+        }"#;
+
+        let expected: [(KeywordMatching, &[&str]); 4] = [
+            (KeywordMatching::default(), &[]),
+            (
+                KeywordMatching {
+                    case_folding: true,
+                    normalization: None,
+                    ..Default::default()
+                },
+                &["A"],
+            ),
+            (
+                KeywordMatching {
+                    case_folding: true,
+                    normalization: Some(NormalizationForm::Nfc),
+                    ..Default::default()
+                },
+                &["A", "B"],
+            ),
+            (
+                KeywordMatching {
+                    case_folding: true,
+                    normalization: Some(NormalizationForm::Nfkc),
+                    ..Default::default()
+                },
+                &["B", "D", "E"],
+            ),
+        ];
+
+        for (keyword_matching, winners) in expected {
+            let options = SolveOptions {
+                keyword_matching,
+                ..Default::default()
+            };
+
+            for (solver, result) in solve_all(manifest_json, round_json, &options) {
+                assert_eq!(
+                    result,
+                    ContestResult::from_iter(winners.iter().map(|author| (*author, 1))),
+                    "{solver} {:?}",
+                    options.keyword_matching
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn punctuation_is_trimmed_from_title_words() {
+        let manifest_json = r#"{
+            "categories": [
+                { "keywords": ["Ode"] },
+                { "keywords": ["Autumn"] },
+                { "keywords": ["—"] }
+            ],
+            "rounds": ["round_0.json"]
+        }"#;
+
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "Ode, to Autumn!", "contents": "aaaa" },
+                { "author": "B", "title": "Autumn", "contents": "bbb" },
+                { "author": "C", "title": "— untitled —", "contents": "cc" }
+            ]
+        }"#;
+
+        // "Ode," and "Autumn!" only match once trimmed, "—" is not trimmed as nothing would be
+        // left of it.
+        let expected = [
+            (None, vec![("B", 1), ("C", 1)]),
+            (Some(Punctuation::default()), vec![("A", 2), ("C", 1)]),
+            (Some(Punctuation::new("!")), vec![("A", 1), ("C", 1)]),
+        ];
+
+        for (trim_punctuation, scores) in expected {
+            let options = SolveOptions {
+                keyword_matching: KeywordMatching {
+                    trim_punctuation,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            for (solver, result) in solve_all(manifest_json, round_json, &options) {
+                assert_eq!(
+                    result,
+                    ContestResult::from_iter(scores.iter().copied()),
+                    "{solver} {:?}",
+                    options.keyword_matching
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn punctuation_is_trimmed_from_keywords() {
+        let manifest_json = r#"{
+            "categories": [
+                { "keywords": ["Ode,"] },
+                { "keywords": ["(to Autumn)"] }
+            ],
+            "rounds": ["round_0.json"]
+        }"#;
+
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "Ode to Autumn", "contents": "aaaa" }
+            ]
+        }"#;
+
+        let expected = [
+            (None, vec![]),
+            (Some(Punctuation::default()), vec![("A", 2)]),
+        ];
+
+        for (trim_punctuation, scores) in expected {
+            let options = SolveOptions {
+                keyword_matching: KeywordMatching {
+                    trim_punctuation,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            for (solver, result) in solve_all(manifest_json, round_json, &options) {
+                assert_eq!(
+                    result,
+                    ContestResult::from_iter(scores.iter().copied()),
+                    "{solver} {:?}",
+                    options.keyword_matching
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn all_solvers_match_phrase_keywords() {
+        let manifest_json = r#"{
+            "categories": [
+                { "keywords": ["autumn leaves", "maple"] },
+                { "keywords": ["leaves"] },
+                { "keywords": ["leaves fall", "autumn   leaves"] }
+            ],
+            "rounds": ["round_0.json"]
+        }"#;
+
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "autumn leaves", "contents": "aa" },
+                { "author": "B", "title": "leaves autumn", "contents": "bbb" },
+                { "author": "C", "title": "as autumn\nleaves fall", "contents": "c" },
+                { "author": "D", "title": "autumn, leaves", "contents": "dddd" }
+            ]
+        }"#;
+
+        // Category 0: A and C, category 1: all of them, category 2: A and C.
+        for (solver, result) in solve_all(manifest_json, round_json, &SolveOptions::default()) {
+            assert_eq!(
+                result,
+                ContestResult::from_iter([("A", 2), ("D", 1)]),
+                "{solver}"
+            );
+        }
+    }
+
+    #[test]
+    fn all_solvers_apply_tie_policy() {
+        // Rose: B and C tie at 6.0, B twice. Blue: C and D tie at 6.0. By character density,
+        // C beats B but is still tied with D.
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "rose", "contents": "aa bb" },
+                { "author": "B", "title": "rose", "contents": "\u00e9\u00e9\u00e9" },
+                { "author": "C", "title": "rose blue", "contents": "cccccc" },
+                { "author": "B", "title": "rose", "contents": "\u00e9\u00e9\u00e9" },
+                { "author": "D", "title": "blue", "contents": "dddddd" }
+            ]
+        }"#;
+
+        let half = Score::new(1, 2);
+        let expected: [(&str, Vec<(&str, Score)>); 6] = [
+            (
+                "",
+                vec![("B", Score::ONE), ("C", Score::whole(2)), ("D", Score::ONE)],
+            ),
+            (
+                r#", "tie_policy": "all_tied""#,
+                vec![("B", Score::ONE), ("C", Score::whole(2)), ("D", Score::ONE)],
+            ),
+            (
+                r#", "tie_policy": "earliest_entry""#,
+                vec![("B", Score::ONE), ("C", Score::ONE)],
+            ),
+            (
+                r#", "tie_policy": "fractional_split""#,
+                vec![("B", half), ("C", Score::ONE), ("D", half)],
+            ),
+            (r#", "tie_policy": "no_award""#, vec![]),
+            (
+                r#", "tie_policy": { "secondary_weight": "char_density" }"#,
+                vec![("C", Score::whole(2)), ("D", Score::ONE)],
+            ),
+        ];
+
+        for (tie_policy, scores) in expected {
+            let manifest_json = format!(
+                r#"{{
+                    "categories": [{{ "keywords": ["rose"] }}, {{ "keywords": ["blue"] }}],
+                    "rounds": ["round_0.json"]
+                    {tie_policy}
+                }}"#
+            );
+
+            for (solver, result) in solve_all(&manifest_json, round_json, &SolveOptions::default())
+            {
+                assert_eq!(
+                    result,
+                    ContestResult::from_iter(scores.iter().copied()),
+                    "{solver} {tie_policy}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn score_overflow_is_reported() {
+        // A is tied with 1, 2, 4, ... other authors in each category, so A would get 1/2 + 1/3 +
+        // 1/5 + ... + 1/53 points, whose denominator does not fit into a u64.
+        let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+
+        let categories = (0..primes.len())
+            .map(|index| format!(r#"{{ "keywords": ["k{index}"] }}"#))
+            .collect::<Vec<_>>();
+        let manifest_json = format!(
+            r#"{{
+                "categories": [{}],
+                "rounds": ["round_0.json"],
+                "tie_policy": "fractional_split"
+            }}"#,
+            categories.join(", ")
+        );
+
+        let all_keywords = (0..primes.len())
+            .map(|index| format!("k{index}"))
+            .collect::<Vec<_>>();
+        let mut entries = vec![format!(
+            r#"{{ "author": "A", "title": "{}", "contents": "x" }}"#,
+            all_keywords.join(" ")
+        )];
+
+        for (index, prime) in primes.into_iter().enumerate() {
+            for other in 1..prime {
+                entries.push(format!(
+                    r#"{{ "author": "B{index}-{other}", "title": "k{index}", "contents": "x" }}"#
+                ));
+            }
+        }
+
+        let round_json = format!(r#"{{ "entries": [{}] }}"#, entries.join(", "));
+
+        for (solver, error) in solve_all_err(&manifest_json, &round_json) {
+            assert!(
+                matches!(&error, ContestError::ScoreOverflow { round } if round == Path::new("round_0.json")),
+                "{solver}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn all_solvers_apply_category_points_and_round_multiplier() {
+        let manifest_json = r#"{
+            "categories": [
+                { "keywords": ["rose"], "points": 3 },
+                { "keywords": ["blue"] }
+            ],
+            "rounds": ["round_0.json", "round_1.json"],
+            "tie_policy": "fractional_split"
+        }"#;
+
+        let rounds = [
+            r#"{
+                "entries": [
+                    { "author": "A", "title": "rose", "contents": "aaaa" },
+                    { "author": "B", "title": "blue", "contents": "bb" }
+                ]
+            }"#,
+            r#"{
+                "multiplier": 2,
+                "entries": [
+                    { "author": "A", "title": "rose blue", "contents": "aa" },
+                    { "author": "B", "title": "rose", "contents": "bb" },
+                    { "author": "C", "title": "blue", "contents": "c" }
+                ]
+            }"#,
+        ];
+
+        let options = SolveOptions {
+            round_details: true,
+            ..Default::default()
+        };
+
+        for (solver, result) in solve_all_rounds(manifest_json, &rounds, &options) {
+            // Round 0: A wins rose (3), B wins blue (1). Round 1: A and B split rose (2 * 3),
+            // A wins blue (2 * 1).
+            assert_eq!(
+                result.scores(),
+                ContestResult::from_iter([("A", 8), ("B", 4)]).scores(),
+                "{solver}"
+            );
+
+            let points = result
+                .rounds()
+                .unwrap()
+                .iter()
+                .map(|round| {
+                    round
+                        .categories
+                        .iter()
+                        .map(|winners| winners.points)
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                points,
+                [
+                    vec![Score::whole(3), Score::ONE],
+                    vec![Score::whole(3), Score::whole(2)]
+                ],
+                "{solver}"
+            );
+        }
+    }
+
+    #[test]
+    fn all_solvers_award_podium_places() {
+        let manifest_json = r#"{
+            "categories": [
+                { "keywords": ["rose"], "points": 2 },
+                { "keywords": ["blue"] }
+            ],
+            "rounds": ["round_0.json"],
+            "podium": [3, 2, 1]
+        }"#;
+
+        // Rose: A (6.0) first, B and C (3.0) second, D (2.0) third without A, who is already
+        // first, and E (1.0) too late. Blue: D alone.
+        let round_json = r#"{
+            "entries": [
+                { "author": "A", "title": "rose", "contents": "aaaaaa" },
+                { "author": "B", "title": "rose", "contents": "bbb" },
+                { "author": "A", "title": "rose", "contents": "aa" },
+                { "author": "C", "title": "rose", "contents": "ccc" },
+                { "author": "D", "title": "rose blue", "contents": "dd" },
+                { "author": "E", "title": "rose", "contents": "e" }
+            ]
+        }"#;
+
+        let options = SolveOptions {
+            round_details: true,
+            ..Default::default()
+        };
+
+        for (solver, result) in solve_all(manifest_json, round_json, &options) {
+            assert_eq!(
+                result.scores(),
+                ContestResult::from_iter([("A", 6), ("B", 4), ("C", 4), ("D", 5)]).scores(),
+                "{solver}"
+            );
+
+            let places = result.rounds().unwrap()[0]
+                .categories
+                .iter()
+                .map(|winners| {
+                    (
+                        winners.category,
+                        winners.place,
+                        winners.authors.clone(),
+                        winners.points,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                places,
+                [
+                    (0, 1, vec!["A".to_owned()], Score::whole(6)),
+                    (0, 2, vec!["B".to_owned(), "C".to_owned()], Score::whole(4)),
+                    (0, 3, vec!["D".to_owned()], Score::whole(2)),
+                    (1, 1, vec!["D".to_owned()], Score::whole(3)),
+                ],
+                "{solver}"
+            );
+        }
+    }
+
+    #[test]
+    fn zero_points_are_rejected() {
+        let manifest_json = r#"{
+            "categories": [{ "keywords": ["rose"], "points": 0 }],
+            "rounds": ["round_0.json"]
+        }"#;
+
+        for (solver, error) in solve_all_err(manifest_json, ROUND_JSON) {
+            assert!(
+                matches!(error, ContestError::MalformedManifest { .. }),
+                "{solver}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn empty_podium_is_rejected() {
+        let manifest_json = r#"{
+            "categories": [{ "keywords": ["rose"] }],
+            "rounds": ["round_0.json"],
+            "podium": []
+        }"#;
+
+        for (solver, error) in solve_all_err(manifest_json, ROUND_JSON) {
+            assert!(
+                matches!(error, ContestError::MalformedManifest { line: 4, .. }),
+                "{solver}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn points_overflow_is_reported() {
+        let manifest_json = |podium| {
+            format!(
+                r#"{{
+                    "categories": [
+                        {{ "keywords": ["red"], "points": 2 }},
+                        {{ "keywords": ["rose"], "points": 18446744073709551615 }}
+                    ],
+                    "rounds": ["round_0.json"],
+                    "podium": {podium}
+                }}"#
+            )
+        };
+
+        // The first place of the second category is worth u64::MAX points, which fits, but not
+        // twice that. The first category stays well within the limit either way.
+        let round_json = |multiplier| {
+            format!(
+                r#"{{
+                    "multiplier": {multiplier},
+                    "entries": [{{ "author": "A", "title": "rose", "contents": "a" }}]
+                }}"#
+            )
+        };
+
+        for (solver, result) in solve_all(
+            &manifest_json("[1]"),
+            &round_json(1),
+            &SolveOptions::default(),
+        ) {
+            assert_eq!(result.total(), u64::MAX, "{solver}");
+        }
+
+        for (solver, error) in solve_all_err(&manifest_json("[2, 1]"), &round_json(1)) {
+            assert!(
+                matches!(
+                    error,
+                    ContestError::PointsOverflow {
+                        category: 1,
+                        round: None
+                    }
+                ),
+                "{solver}: {error}"
+            );
+        }
+
+        for (solver, error) in solve_all_err(&manifest_json("[1]"), &round_json(2)) {
+            assert!(
+                matches!(&error, ContestError::PointsOverflow { category: 1, round: Some(round) } if round == Path::new("round_0.json")),
+                "{solver}: {error}"
+            );
+        }
+    }
+
+    /// Every built-in weight picks a different winner: A has the most bytes per word, B the most
+    /// characters per word and the most distinct words, C the most lines, D the most syllables.
+    const WEIGHTS_ROUND_JSON: &str = r#"{
+        "entries": [
+            { "author": "A", "title": "poem", "contents": "\u00e9\u00e9\u00e9\u00e9\u00e9\u00e9 \u00e9\u00e9\u00e9\u00e9\u00e9\u00e9" },
+            { "author": "B", "title": "poem", "contents": "bbbbbbbbbbbbbbbbbbbb bb" },
+            { "author": "C", "title": "poem", "contents": "x\ny\ny" },
+            { "author": "D", "title": "poem", "contents": "beautiful beautiful" }
+        ]
+    }"#;
+
+    fn weights_manifest_json(weight: &str) -> String {
+        format!(
+            r#"{{
+                "categories": [{{ "keywords": ["poem"] }}],
+                "rounds": ["round_0.json"]
+                {weight}
+            }}"#
+        )
+    }
+
+    #[test]
+    fn manifest_selects_weight_function() {
+        for (weight, winner) in [
+            ("", "A"),
+            (r#", "weight": "word_density""#, "A"),
+            (r#", "weight": "char_density""#, "B"),
+            (r#", "weight": "line_count""#, "C"),
+            (r#", "weight": "distinct_word_ratio""#, "B"),
+            (r#", "weight": "syllable_density""#, "D"),
+        ] {
+            let manifest_json = weights_manifest_json(weight);
+
+            for (solver, result) in
+                solve_all(&manifest_json, WEIGHTS_ROUND_JSON, &SolveOptions::default())
+            {
+                assert_eq!(
+                    result,
+                    ContestResult::from_iter([(winner, 1)]),
+                    "{solver} {weight}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn options_override_weight_function() {
+        /// The shorter the contents, the better.
+        #[derive(Debug)]
+        struct Brevity;
+
+        impl WeightFunction for Brevity {
+            fn weight(&self, contents: &str, _: WordBoundary) -> f64 {
+                -(contents.len() as f64)
+            }
+        }
+
+        let options = SolveOptions {
+            weight_function: Some(Arc::new(Brevity)),
+            ..Default::default()
+        };
+
+        let manifest_json = weights_manifest_json(r#", "weight": "char_density""#);
+
+        for (solver, result) in solve_all(&manifest_json, WEIGHTS_ROUND_JSON, &options) {
+            assert_eq!(result, ContestResult::from_iter([("C", 1)]), "{solver}");
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "weight function returned NaN")]
+    fn nan_weight_is_rejected() {
+        #[derive(Debug)]
+        struct Undefined;
+
+        impl WeightFunction for Undefined {
+            fn weight(&self, _: &str, _: WordBoundary) -> f64 {
+                f64::NAN
+            }
+        }
+
+        let options = SolveOptions {
+            weight_function: Some(Arc::new(Undefined)),
+            ..Default::default()
+        };
+
+        solve_all(MANIFEST_JSON, ROUND_JSON, &options);
+    }
+
+    #[test]
+    fn rounds_are_resolved_relative_to_manifest() {
+        let data_dir = env::temp_dir().join(format!("poetry-contest-{}", process::id()));
+        let round_dir = data_dir.join("rounds");
+        fs::create_dir_all(&round_dir).unwrap();
+
+        let manifest_path = data_dir.join("contest.json");
+        fs::write(
+            &manifest_path,
+            MANIFEST_JSON.replace("round_0.json", "rounds/round_0.json"),
+        )
+        .unwrap();
+        fs::write(round_dir.join("round_0.json"), ROUND_JSON).unwrap();
+
+        for solver in solvers() {
+            let total = solver.solve_manifest(&manifest_path).unwrap().total();
+            assert_eq!(total, 3, "{}", solver.name());
+        }
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn missing_manifest_is_reported() {
+        for solver in solvers() {
+            let error = solver.solve_dir(Path::new("does/not/exist")).unwrap_err();
+            assert!(
+                matches!(error, ContestError::MissingManifest { .. }),
+                "{}: {error}",
+                solver.name()
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_manifest_is_reported() {
+        for solver in solvers() {
+            let manifest_json = "{\n  \"categories\": 42\n}";
+            let error = solver
+                .solve_str(manifest_json, &mut load_round)
+                .unwrap_err();
+            assert!(
+                matches!(error, ContestError::MalformedManifest { line: 2, .. }),
+                "{}: {error}",
+                solver.name()
+            );
+        }
+    }
+
+    #[test]
+    fn unreadable_round_is_reported() {
+        for solver in solvers() {
+            let error = solver
+                .solve_str(MANIFEST_JSON, &mut |_, _| {
+                    Err(io::ErrorKind::NotFound.into())
+                })
+                .unwrap_err();
+            assert!(
+                matches!(&error, ContestError::UnreadableRound { path, .. } if path == Path::new("round_0.json")),
+                "{}: {error}",
+                solver.name()
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_round_is_reported() {
+        for solver in solvers() {
+            let error = solver
+                .solve_str(MANIFEST_JSON, &mut |_, round_json| {
+                    round_json.push_str("{\n  \"entries\": [\n    { \"author\": 42 }\n  ]\n}");
+                    Ok(())
+                })
+                .unwrap_err();
+            assert!(
+                matches!(&error, ContestError::MalformedRound { path, line: 3, .. } if path == Path::new("round_0.json")),
+                "{}: {error}",
+                solver.name()
+            );
+        }
+    }
+}
@@ -0,0 +1,446 @@
+//! Rules for splitting titles and contents into words and for matching the words of a title
+//! against keywords, shared by all the solvers so that they agree on what a word is.
+
+use std::{
+    borrow::Cow,
+    str::{SplitAsciiWhitespace, SplitWhitespace},
+};
+
+use caseless::Caseless;
+use unicode_normalization::{UnicodeNormalization, is_nfc, is_nfkc};
+use unicode_segmentation::{UnicodeSegmentation, UnicodeWords};
+
+/// Defines where one word ends and the next one begins, both when matching the words of a title
+/// against the keywords of the categories and when counting the words of the contents to
+/// calculate the weight of an entry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WordBoundary {
+    /// Words are separated by characters with the Unicode `White_Space` property, as in
+    /// [`str::split_whitespace`]. This includes no-break and ideographic spaces.
+    #[default]
+    UnicodeWhitespace,
+
+    /// Words are separated by ASCII whitespace only, as in [`str::split_ascii_whitespace`].
+    /// Any other character, including non-ASCII whitespace, is part of a word.
+    AsciiWhitespace,
+
+    /// Words are found by Unicode word segmentation as specified by
+    /// [UAX #29](https://www.unicode.org/reports/tr29/). Punctuation and whitespace are not part
+    /// of any word and scripts written without spaces are split into individual words.
+    UnicodeWords,
+}
+
+impl WordBoundary {
+    /// Every policy, in the order they are declared.
+    pub const ALL: [WordBoundary; 3] = [
+        WordBoundary::UnicodeWhitespace,
+        WordBoundary::AsciiWhitespace,
+        WordBoundary::UnicodeWords,
+    ];
+
+    /// Short name of the policy, as accepted by [`from_name()`][Self::from_name].
+    pub fn name(self) -> &'static str {
+        match self {
+            WordBoundary::UnicodeWhitespace => "unicode-whitespace",
+            WordBoundary::AsciiWhitespace => "ascii-whitespace",
+            WordBoundary::UnicodeWords => "unicode-words",
+        }
+    }
+
+    /// Finds a policy by its short name (e.g. `ascii-whitespace`).
+    pub fn from_name(name: &str) -> Option<WordBoundary> {
+        WordBoundary::ALL
+            .into_iter()
+            .find(|boundary| boundary.name() == name)
+    }
+
+    /// The words of `text`, in order.
+    pub fn words(self, text: &str) -> Words<'_> {
+        match self {
+            WordBoundary::UnicodeWhitespace => Words::UnicodeWhitespace(text.split_whitespace()),
+            WordBoundary::AsciiWhitespace => Words::AsciiWhitespace(text.split_ascii_whitespace()),
+            WordBoundary::UnicodeWords => Words::UnicodeWords(text.unicode_words()),
+        }
+    }
+
+    /// The number of words in `text`.
+    pub fn word_count(self, text: &str) -> usize {
+        self.words(text).count()
+    }
+
+    /// Whether `text` contains at least one word. Contents without any words are disqualified.
+    pub fn has_words(self, text: &str) -> bool {
+        // Cheaper than looking for the first word, which is what this is equivalent to.
+        match self {
+            WordBoundary::UnicodeWhitespace => !text.trim().is_empty(),
+            WordBoundary::AsciiWhitespace => !text.trim_ascii().is_empty(),
+            WordBoundary::UnicodeWords => text.unicode_words().next().is_some(),
+        }
+    }
+}
+
+/// Iterator over the words of a text, created by [`WordBoundary::words()`].
+pub enum Words<'a> {
+    UnicodeWhitespace(SplitWhitespace<'a>),
+    AsciiWhitespace(SplitAsciiWhitespace<'a>),
+    UnicodeWords(UnicodeWords<'a>),
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        match self {
+            Words::UnicodeWhitespace(words) => words.next(),
+            Words::AsciiWhitespace(words) => words.next(),
+            Words::UnicodeWords(words) => words.next(),
+        }
+    }
+}
+
+/// Defines when a word of a title matches a keyword.
+///
+/// By default, a word matches a keyword only if they are identical byte for byte. Otherwise,
+/// both the keywords and the words are converted to a common form before they are compared,
+/// the keywords once when the solver indexes them and the words whenever they are looked up.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeywordMatching {
+    /// Punctuation trimmed from both ends of every word of a title, so that e.g. "Ode," in
+    /// "Ode, to Autumn!" matches the keyword "Ode". Keywords are trimmed the same way, as a
+    /// keyword like "Ode," could otherwise never match.
+    pub trim_punctuation: Option<Punctuation>,
+
+    /// Whether words that only differ in case match, e.g. "Love" and "love". Uses Unicode
+    /// default case folding, so "STRASSE" also matches "straße".
+    pub case_folding: bool,
+
+    /// Normalization form both keywords and words are converted to, so that e.g. a precomposed
+    /// "é" matches "e" followed by a combining acute accent. Case folding is applied first,
+    /// and again after compatibility normalization, which can produce uppercase letters.
+    pub normalization: Option<NormalizationForm>,
+}
+
+/// A set of punctuation characters, see [`KeywordMatching::trim_punctuation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Punctuation {
+    chars: String,
+}
+
+/// Unicode normalization form, see [UAX #15](https://www.unicode.org/reports/tr15/).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Canonical composition. Only unifies different encodings of the same characters.
+    Nfc,
+
+    /// Compatibility composition. Additionally unifies characters that only differ in
+    /// presentation, e.g. the ligature "ﬁ" and "fi" or full-width and regular letters.
+    Nfkc,
+}
+
+impl KeywordMatching {
+    /// Converts a word of a title into the form in which it is compared to the keywords.
+    ///
+    /// A word that only consists of punctuation is not trimmed, so that it never becomes empty.
+    pub fn title_word<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        let trimmed = match &self.trim_punctuation {
+            Some(punctuation) => punctuation.trim(word),
+            None => word,
+        };
+
+        if trimmed.is_empty() {
+            self.normalize(word)
+        } else {
+            self.normalize(trimmed)
+        }
+    }
+
+    /// Converts a keyword, or a word of a phrase keyword, into the form in which it is compared
+    /// to the words of titles, which is exactly what happens to the words of titles.
+    pub fn keyword<'a>(&self, keyword: &'a str) -> Cow<'a, str> {
+        self.title_word(keyword)
+    }
+
+    /// Converts a keyword or a word of a title into the form in which they are compared, apart
+    /// from trimming punctuation.
+    ///
+    /// The word is borrowed if it is already in that form, which is always the case if no
+    /// conversion is enabled, so the common case does not allocate.
+    pub fn normalize<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        let mut word = Cow::Borrowed(word);
+
+        // Neither case folding nor normalization change anything but ASCII letters in ASCII text.
+        if word.is_ascii() {
+            if self.case_folding && word.bytes().any(|b| b.is_ascii_uppercase()) {
+                word = Cow::Owned(word.to_ascii_lowercase());
+            }
+
+            return word;
+        }
+
+        if self.case_folding && !word.chars().default_case_fold().eq(word.chars()) {
+            word = Cow::Owned(caseless::default_case_fold_str(&word));
+        }
+
+        match self.normalization {
+            Some(NormalizationForm::Nfc) if !is_nfc(&word) => {
+                word = Cow::Owned(word.nfc().collect());
+            }
+            Some(NormalizationForm::Nfkc) if !is_nfkc(&word) => {
+                word = Cow::Owned(word.nfkc().collect());
+
+                // Compatibility decomposition can turn caseless characters into uppercase
+                // letters, e.g. "ℌ" into "H", so we fold again. Folding can leave the word
+                // in another normalization form, hence normalizing once more, like NFKC_Casefold.
+                if self.case_folding && !word.chars().default_case_fold().eq(word.chars()) {
+                    word = Cow::Owned(caseless::default_case_fold_str(&word).nfkc().collect());
+                }
+            }
+            _ => {}
+        }
+
+        word
+    }
+}
+
+impl Punctuation {
+    /// The punctuation trimmed by default: ASCII punctuation except for symbols that commonly
+    /// stand for words (`#`, `$`, `%`, `&`, `+`, `<`, `=`, `>`, `@`, `^`, `|`, `~`), plus common
+    /// typographic quotes, dashes and the like.
+    pub const DEFAULT_CHARS: &str = "!\"'()*,-./:;?[\\]_`{}¡§«¶·»¿‐‑‒–—―‘’‚‛“”„‟•…‹›";
+
+    /// A set of the given characters.
+    pub fn new(chars: impl Into<String>) -> Punctuation {
+        Punctuation {
+            chars: chars.into(),
+        }
+    }
+
+    /// The characters in the set.
+    pub fn chars(&self) -> &str {
+        &self.chars
+    }
+
+    /// `word` without any characters of the set at its start and end.
+    pub fn trim<'a>(&self, word: &'a str) -> &'a str {
+        word.trim_matches(|c| self.chars.contains(c))
+    }
+}
+
+impl Default for Punctuation {
+    fn default() -> Punctuation {
+        Punctuation::new(Punctuation::DEFAULT_CHARS)
+    }
+}
+
+impl NormalizationForm {
+    /// Short name of the form, as accepted by [`from_name()`][Self::from_name].
+    pub fn name(self) -> &'static str {
+        match self {
+            NormalizationForm::Nfc => "nfc",
+            NormalizationForm::Nfkc => "nfkc",
+        }
+    }
+
+    /// Finds a form by its short name (e.g. `nfkc`).
+    pub fn from_name(name: &str) -> Option<NormalizationForm> {
+        [NormalizationForm::Nfc, NormalizationForm::Nfkc]
+            .into_iter()
+            .find(|form| form.name() == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(boundary: WordBoundary, text: &str) -> Vec<&str> {
+        boundary.words(text).collect()
+    }
+
+    #[test]
+    fn non_breaking_and_ideographic_spaces() {
+        let text = "Autumn\u{a0}moon\u{3000}rises";
+
+        assert_eq!(
+            words(WordBoundary::UnicodeWhitespace, text),
+            ["Autumn", "moon", "rises"]
+        );
+        assert_eq!(words(WordBoundary::AsciiWhitespace, text), [text]);
+        assert_eq!(
+            words(WordBoundary::UnicodeWords, text),
+            ["Autumn", "moon", "rises"]
+        );
+    }
+
+    #[test]
+    fn punctuation() {
+        let text = "Ode, to «l'automne»!";
+
+        assert_eq!(
+            words(WordBoundary::UnicodeWhitespace, text),
+            ["Ode,", "to", "«l'automne»!"]
+        );
+        assert_eq!(
+            words(WordBoundary::AsciiWhitespace, text),
+            ["Ode,", "to", "«l'automne»!"]
+        );
+        assert_eq!(
+            words(WordBoundary::UnicodeWords, text),
+            ["Ode", "to", "l'automne"]
+        );
+    }
+
+    #[test]
+    fn scripts_without_spaces() {
+        let text = "古池や 蛙飛び込む";
+
+        assert_eq!(
+            words(WordBoundary::UnicodeWhitespace, text),
+            ["古池や", "蛙飛び込む"]
+        );
+        assert_eq!(
+            words(WordBoundary::AsciiWhitespace, text),
+            ["古池や", "蛙飛び込む"]
+        );
+        assert_eq!(
+            words(WordBoundary::UnicodeWords, text),
+            ["古", "池", "や", "蛙", "飛", "び", "込", "む"]
+        );
+    }
+
+    #[test]
+    fn has_words_agrees_with_words() {
+        for text in [
+            "",
+            " \n\t",
+            "\u{3000}",
+            "a",
+            " a ",
+            "!?",
+            "\u{a0}-\u{a0}",
+            "池",
+        ] {
+            for boundary in WordBoundary::ALL {
+                assert_eq!(
+                    boundary.has_words(text),
+                    boundary.words(text).next().is_some(),
+                    "{boundary:?} {text:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for boundary in WordBoundary::ALL {
+            assert_eq!(WordBoundary::from_name(boundary.name()), Some(boundary));
+        }
+
+        assert_eq!(WordBoundary::from_name("whitespace"), None);
+    }
+
+    #[test]
+    fn exact_matching_borrows() {
+        let matching = KeywordMatching::default();
+
+        for word in ["Love", "Café", "Cafe\u{301}", "ﬁre"] {
+            assert!(matches!(matching.normalize(word), Cow::Borrowed(w) if w == word));
+        }
+    }
+
+    #[test]
+    fn case_folding() {
+        let matching = KeywordMatching {
+            case_folding: true,
+            ..Default::default()
+        };
+
+        assert_eq!(matching.normalize("Love"), "love");
+        assert_eq!(matching.normalize("STRASSE"), matching.normalize("straße"));
+        assert_eq!(matching.normalize("ΣΟΦΊΑ"), matching.normalize("σοφία"));
+        assert!(matches!(matching.normalize("love"), Cow::Borrowed(_)));
+        assert!(matches!(matching.normalize("été"), Cow::Borrowed(_)));
+
+        // Without normalization, different encodings of the same text still differ.
+        assert_ne!(
+            matching.normalize("Café"),
+            matching.normalize("Cafe\u{301}")
+        );
+    }
+
+    #[test]
+    fn normalization() {
+        let nfc = KeywordMatching {
+            normalization: Some(NormalizationForm::Nfc),
+            ..Default::default()
+        };
+        let nfkc = KeywordMatching {
+            normalization: Some(NormalizationForm::Nfkc),
+            ..Default::default()
+        };
+
+        assert_eq!(nfc.normalize("Cafe\u{301}"), "Café");
+        assert!(matches!(nfc.normalize("Café"), Cow::Borrowed(_)));
+        assert_eq!(nfc.normalize("ﬁre"), "ﬁre");
+        assert_eq!(nfc.normalize("Ｌｏｖｅ"), "Ｌｏｖｅ");
+
+        assert_eq!(nfkc.normalize("Cafe\u{301}"), "Café");
+        assert_eq!(nfkc.normalize("ﬁre"), "fire");
+        assert_eq!(nfkc.normalize("Ｌｏｖｅ"), "Love");
+    }
+
+    #[test]
+    fn case_folding_and_normalization() {
+        let matching = KeywordMatching {
+            case_folding: true,
+            normalization: Some(NormalizationForm::Nfkc),
+            ..Default::default()
+        };
+
+        assert_eq!(matching.normalize("ＬＯＶＥ"), "love");
+        assert_eq!(matching.normalize("CAFE\u{301}"), "café");
+        assert_eq!(matching.normalize("ℌello"), "hello");
+        assert_eq!(matching.normalize("\u{1d40b}ove"), "love");
+    }
+
+    #[test]
+    fn trimming_punctuation() {
+        let matching = KeywordMatching {
+            trim_punctuation: Some(Punctuation::default()),
+            ..Default::default()
+        };
+        let title_words = |title| {
+            WordBoundary::UnicodeWhitespace
+                .words(title)
+                .map(|word| matching.title_word(word))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(title_words("Ode, to Autumn!"), ["Ode", "to", "Autumn"]);
+        assert_eq!(
+            title_words("«Le Lac» — “Ô temps, suspends ton vol…”"),
+            ["Le", "Lac", "—", "Ô", "temps", "suspends", "ton", "vol"]
+        );
+        assert_eq!(
+            title_words("'Tis the season's (last) rose..."),
+            ["Tis", "the", "season's", "last", "rose"]
+        );
+        assert_eq!(title_words("#1 @home"), ["#1", "@home"]);
+        assert!(matches!(matching.title_word("Ode,"), Cow::Borrowed("Ode")));
+        assert_eq!(matching.keyword("Ode,"), matching.title_word("Ode!"));
+        assert_eq!(matching.keyword("—"), "—");
+    }
+
+    #[test]
+    fn trimming_custom_punctuation() {
+        let matching = KeywordMatching {
+            trim_punctuation: Some(Punctuation::new("#*")),
+            case_folding: true,
+            ..Default::default()
+        };
+
+        assert_eq!(matching.title_word("**Love**"), "love");
+        assert_eq!(matching.title_word("#love!"), "love!");
+        assert_eq!(matching.title_word("***"), "***");
+    }
+}
@@ -1,22 +1,26 @@
-use std::{
-    borrow::Cow,
-    cell::RefCell,
-    collections::HashMap,
-    fs::{self, File},
-    io::Read,
-    path::{Path, PathBuf},
-};
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, fs, num::NonZeroU64, path::Path};
 
 use serde::Deserialize;
 use serde_json::value::RawValue;
 use serde_with::{BorrowCow, serde_as};
 
-use crate::find_workspace_root;
+use crate::{
+    ContestError, ContestResult, DisqualificationReason, Rules, Score, SolveOptions, TiePolicy,
+    Weight,
+    error::RoundError,
+    find_workspace_root,
+    judge::Judge,
+    result::ResultBuilder,
+    scoring::{CategoryPodium, EntryWeight, deserialize_podium},
+    solver::{RoundLoader, Solver, read_round_file},
+};
 
 #[test]
 fn run() {
-    let result = solve();
-    println!("{}", result);
+    let data_dir = find_workspace_root().join("data");
+    let result = try_solve(&data_dir).expect("Failed to solve poetry contest");
+    println!("{}", result.total());
+    crate::expected::assert_matches_expected(&data_dir, &result);
 }
 
 /// Solves the poetry contest problem.
@@ -40,27 +44,111 @@ fn run() {
 /// 3. Each round contains a set of entries, each entry is associated with a single author and
 ///    any number of categories (from zero to all categories).
 /// 4. If an entry is longer than 1000 bytes or has no non-whitespace contents, it is disqualified.
+///    The `rules` section of the manifest can change the limit and add others, see [`Rules`].
 /// 5. The weight of an entry is defined as its word density - length in bytes divided by
 ///    number of words (a word is defined as a nonempty sequence of non-whitespace characters
 ///    separated by whitespace). By definition, an entry cannot have zero words (see rule 4).
-/// 6 The categories of an entry are determined by matching the keywords of a category against
+/// 6. The categories of an entry are determined by matching the keywords of a category against
 ///    the words in the title of the entry. A category matches if at least one keyword matches
-///    a word in the title. An entry can match zero or more categories.
+///    a word in the title. A keyword of multiple words (a phrase) matches if its words appear in
+///    the title consecutively. An entry can match zero or more categories.
 /// 7. In each round, the entry with the highest weight in each category yields 1 point for its
 ///    author. The same entry may yield points for multiple categories. In case of a tie in some
 ///    category, all authors with the highest weight receive 1 point for that category. If the
 ///    same author has multiple entries in the tie, they only get 1 point total for that category.
+///    The `tie_policy` field of the manifest can settle ties differently, see [`TiePolicy`].
+///    A category of the manifest can be worth more `points` than 1, and a round file can set a
+///    `multiplier` for the points of all its categories.
+///    With a `podium` in the manifest, like `[3, 2, 1]`, the entries with the best few distinct
+///    weights are awarded the points of their place instead, each author only for their best.
+///
+/// Whitespace is any character with the Unicode `White_Space` property. A different definition
+/// of a word can be selected via [`SolveOptions::word_boundary`], which applies to rules 4 to 6.
+/// Keywords match words byte for byte, unless [`SolveOptions::keyword_matching`] relaxes rule 6.
+/// The `weight` field of the manifest can select another [`Weight`] for rule 5, and the
+/// [`SolveOptions::weight_function`] can replace it with a custom one.
+///
+/// Returns the total points of all authors, and panics if split ties leave a fraction of a
+/// point in it. [`ContestResult::total()`] has the exact total of any contest.
 pub fn solve() -> u64 {
     let workspace_root = find_workspace_root();
-    let data_dir = workspace_root.join("data");
-    let manifest_path = data_dir.join("manifest.json");
+    let total = try_solve(&workspace_root.join("data"))
+        .expect("Failed to solve poetry contest")
+        .total();
+
+    assert!(total.is_whole(), "total of {total} points is not whole");
+    total.numerator()
+}
+
+/// Reuses one buffer for reading all the round files.
+pub struct ReuseMore;
+
+impl Solver for ReuseMore {
+    fn name(&self) -> &'static str {
+        "v07_reuse_more"
+    }
+
+    fn description(&self) -> &'static str {
+        "Reuses a single buffer for reading all the round files."
+    }
+
+    fn solve_manifest_with(
+        &self,
+        manifest_path: &Path,
+        options: &SolveOptions,
+    ) -> Result<ContestResult, ContestError> {
+        solve_manifest_with(manifest_path, options)
+    }
+
+    fn solve_str_with(
+        &self,
+        manifest_json: &str,
+        load_round: &mut RoundLoader<'_>,
+        options: &SolveOptions,
+    ) -> Result<ContestResult, ContestError> {
+        solve_inner(manifest_json, load_round, options)
+    }
+}
+
+/// Solves the poetry contest for the data set in `data_dir`, returning an error instead of
+/// panicking if the data set cannot be read or parsed.
+///
+/// See [`solve()`] for the scoring rules.
+pub fn try_solve(data_dir: &Path) -> Result<ContestResult, ContestError> {
+    solve_manifest(&data_dir.join("manifest.json"))
+}
 
-    let manifest_json = fs::read_to_string(&manifest_path).expect("Failed to read manifest.json");
-    solve_inner(data_dir, &manifest_json)
+/// Solves the poetry contest for the data set described by the manifest at `manifest_path`.
+///
+/// Round paths listed in the manifest are resolved relative to the directory that contains the
+/// manifest, so the data set can be located anywhere on the filesystem.
+pub fn solve_manifest(manifest_path: &Path) -> Result<ContestResult, ContestError> {
+    solve_manifest_with(manifest_path, &SolveOptions::default())
+}
+
+/// Same as [`solve_manifest()`], additionally recording whatever the options request.
+pub fn solve_manifest_with(
+    manifest_path: &Path,
+    options: &SolveOptions,
+) -> Result<ContestResult, ContestError> {
+    let data_dir = manifest_path.parent().unwrap_or(Path::new(""));
+
+    let manifest_json = fs::read_to_string(manifest_path)
+        .map_err(|e| ContestError::missing_manifest(manifest_path, e))?;
+    solve_inner(
+        &manifest_json,
+        &mut |round_path, round_json| read_round_file(data_dir, round_path, round_json),
+        options,
+    )
 }
 
-fn solve_inner<'manifest>(data_dir: PathBuf, manifest_json: &'manifest str) -> u64 {
-    let manifest: Manifest = serde_json::from_str(&manifest_json).unwrap();
+fn solve_inner(
+    manifest_json: &str,
+    load_round: &mut RoundLoader<'_>,
+    options: &SolveOptions,
+) -> Result<ContestResult, ContestError> {
+    let manifest: Manifest =
+        serde_json::from_str(manifest_json).map_err(ContestError::malformed_manifest)?;
 
     // This will overshoot a bit if multiple categories share the same keyword. That's fine - good
     // enough. The main thing we want to avoid is repeated incremental growth of the collection.
@@ -71,71 +159,104 @@ fn solve_inner<'manifest>(data_dir: PathBuf, manifest_json: &'manifest str) -> u
         .sum::<usize>();
 
     // Build a HashMap for efficient keyword lookup
-    // Key: keyword, Value: list of category indices that contain this keyword
-    let mut keyword_to_categories: HashMap<&str, Vec<usize>> =
+    // Key: keyword in the form it is matched in, Value: list of category indices that contain it
+    let mut keyword_to_categories: HashMap<Cow<str>, Vec<usize>> =
         HashMap::with_capacity(keyword_count);
     for (cat_idx, category) in manifest.categories.iter().enumerate() {
         for keyword in &category.keywords {
             keyword_to_categories
-                .entry(keyword)
-                .or_insert_with(Vec::new)
+                .entry(options.keyword_matching.keyword(keyword))
+                .or_default()
                 .push(cat_idx);
         }
     }
 
-    let mut points_by_author: HashMap<String, u64> = HashMap::new();
+    // Everything else that decides which categories an entry matches and what weight it has.
+    let judge = Judge::new(
+        manifest
+            .categories
+            .iter()
+            .map(|category| (&category.keywords[..], category.points)),
+        &manifest.rules,
+        manifest.weight.function(),
+        manifest.tie_policy,
+        manifest.podium.as_deref(),
+        options,
+    )?;
+
+    let mut points_by_author: HashMap<String, Score> = HashMap::new();
+    let mut details = ResultBuilder::new(options);
+
+    for category in &manifest.categories {
+        details.record_category(
+            category.id.as_deref(),
+            category.name.as_deref(),
+            category.description.as_deref(),
+        );
+    }
 
     // We reuse the same String for reading round files to avoid repeated allocations.
     let mut round_json = String::new();
 
     for round_path in &manifest.rounds {
         let as_path = Path::new(&**round_path);
-        let round_file_path = data_dir.join(&as_path);
 
         round_json.clear();
 
-        File::open(round_file_path)
-            .expect("Failed to open round file")
-            .read_to_string(&mut round_json)
-            .expect("Failed to read round file");
+        load_round(as_path, &mut round_json)
+            .map_err(|e| ContestError::unreadable_round(as_path, e))?;
+
+        details.start_round(as_path);
 
         solve_round(
             &manifest,
             &round_json,
             &keyword_to_categories,
+            &judge,
             &mut points_by_author,
-        );
+            &mut details,
+        )
+        .map_err(|e| e.in_round(as_path))?;
     }
 
-    // Calculate final output: total score of all authors.
-    points_by_author.values().copied().sum::<u64>()
+    // Final output: the score of every author, from which the caller can derive the leaderboard.
+    Ok(details.finish(points_by_author))
 }
 
 fn solve_round<'manifest, 'round>(
     manifest: &'manifest Manifest<'manifest>,
     round_json: &'round str,
-    keyword_to_categories: &HashMap<&str, Vec<usize>>,
-    points_by_author: &mut HashMap<String, u64>,
-) {
-    let round: Round<'round> = serde_json::from_str(&round_json).unwrap();
-    let entries = parse_entries(round);
+    keyword_to_categories: &HashMap<Cow<str>, Vec<usize>>,
+    judge: &Judge,
+    points_by_author: &mut HashMap<String, Score>,
+    details: &mut ResultBuilder,
+) -> Result<(), RoundError> {
+    let round: Round<'round> = serde_json::from_str(round_json)?;
+    let multiplier = judge.multiplier(round.multiplier)?;
+    let entries = parse_entries(round, judge, details);
 
     // Key: category index.
-    // Value: (best weight, list of authors with that weight).
-    let mut best_by_category: HashMap<usize, (f64, Vec<Cow<'round, str>>)> =
+    // Value: best weights, with the authors (and optionally entries) that have each weight.
+    let mut best_by_category: HashMap<usize, CategoryPodium<Cow<'round, str>>> =
         HashMap::with_capacity(manifest.categories.len());
 
     // We reuse this between entries to avoid repeated allocations.
     let mut matched_categories = Vec::new();
 
+    // Only needed for the round details, so we skip it unless requested.
+    let record_entries = details.records_winners();
+    let record_disqualifications = details.records_disqualifications();
+
     // For each entry, determine its categories and weight, and update
     // the best_by_category map accordingly.
-    for entry in entries {
-        let words = entry.title.split_whitespace();
+    for (entry_idx, entry) in entries {
+        let words = judge.word_boundary.words(&entry.title);
 
         // Use the keyword lookup HashMap for efficient categorization
         for word in words {
-            if let Some(cat_indices) = keyword_to_categories.get(word) {
+            let word = judge.keyword_matching.title_word(word);
+
+            if let Some(cat_indices) = keyword_to_categories.get(&*word) {
                 for &cat_idx in cat_indices {
                     if !matched_categories.contains(&cat_idx) {
                         matched_categories.push(cat_idx);
@@ -144,81 +265,116 @@ fn solve_round<'manifest, 'round>(
             }
         }
 
+        judge
+            .phrases
+            .match_categories(&entry.title, &mut matched_categories);
+
         if matched_categories.is_empty() {
+            // Only entries in some category need a weight but if the caller wants to know about
+            // every disqualified entry, we still need to look at the contents of the others.
+            if record_disqualifications && let Err(reason) = calculate_weight(entry.contents, judge)
+            {
+                details.record_disqualification(entry_idx, &entry.author, reason);
+            }
+
             continue;
         }
 
-        let Some(weight) = calculate_weight(&entry.contents) else {
-            // Entry disqualified.
-            continue;
+        let weight = match calculate_weight(entry.contents, judge) {
+            Ok(weight) => weight,
+            Err(reason) => {
+                // Entry disqualified. We have to forget its categories, as they are otherwise
+                // only cleared when awarding points below.
+                details.record_disqualification(entry_idx, &entry.author, reason);
+                matched_categories.clear();
+                continue;
+            }
         };
 
         for cat_idx in matched_categories.drain(..) {
             let entry_author = entry.author.clone();
 
-            let best_entry = best_by_category.entry(cat_idx).or_insert((weight, vec![]));
-
-            if weight > best_entry.0 {
-                // New best weight, replace existing authors.
-                best_entry.0 = weight;
-                best_entry.1.clear();
-                best_entry.1.push(entry_author);
-            } else if weight == best_entry.0 {
-                // Tie for best weight, add author if not already present.
-                if !best_entry.1.contains(&entry_author) {
-                    best_entry.1.push(entry_author);
-                }
-            }
+            best_by_category
+                .entry(cat_idx)
+                .or_insert_with(|| CategoryPodium::new(judge.places()))
+                .offer(weight, entry_author, entry_idx, record_entries);
         }
     }
 
     // Award points to authors with best entries in each category.
-    for (_cat_idx, (_best_weight, authors)) in best_by_category {
-        for author in authors {
-            // Note: The author is a Cow<'round, str>, we convert it to String for storage.
-            // For lookup we use the Cow because we expect the author is typically already in
-            // the map (from previous rounds or entries). This avoids having to create a new
-            // string for every lookup - we only create owned strings for insertion.
-            if let Some(existing_entry) = points_by_author.get_mut(author.as_ref()) {
-                *existing_entry += 1;
-                continue;
+    for (cat_idx, podium) in best_by_category {
+        let places = podium.award(judge.tie_policy, |place| {
+            judge.points(cat_idx, place, multiplier)
+        });
+
+        for (place, winners) in places.into_iter().enumerate() {
+            if record_entries {
+                details.record_winners(cat_idx, place, &winners);
             }
 
-            // An existing entry did not exist, so insert a new entry.
-            points_by_author.insert(author.into_owned(), 1);
+            let points = winners.points;
+
+            for author in winners.authors {
+                // Note: The author is a Cow<'round, str>, we convert it to String for storage.
+                // For lookup we use the Cow because we expect the author is typically already in
+                // the map (from previous rounds or entries). This avoids having to create a new
+                // string for every lookup - we only create owned strings for insertion.
+                if let Some(existing_entry) = points_by_author.get_mut(author.as_ref()) {
+                    details.award(existing_entry, points)?;
+                    continue;
+                }
+
+                // An existing entry did not exist, so insert a new entry.
+                details.award(
+                    points_by_author.entry(author.into_owned()).or_default(),
+                    points,
+                )?;
+            }
         }
     }
+
+    Ok(())
 }
 
-fn parse_entries<'round>(round: Round<'round>) -> Vec<Entry<'round>> {
+fn parse_entries<'round>(
+    round: Round<'round>,
+    judge: &Judge,
+    details: &mut ResultBuilder,
+) -> Vec<(usize, Entry<'round>)> {
+    // We keep the index of each entry in the round, to identify the entries in the round details.
     round
         .entries
         .into_iter()
-        .filter(|e| {
-            let len = calculate_json_string_length(&e.contents);
-
-            let Some(len) = len else {
+        .enumerate()
+        .filter(|(entry_idx, e)| {
+            let reason = match calculate_json_string_length(e.contents) {
+                Ok(len) if len <= judge.max_bytes => return true,
+                Ok(_) => DisqualificationReason::TooLong,
                 // Disqualified due to invalid format or escape sequence.
-                return false;
+                Err(reason) => reason,
             };
 
-            len <= 1000
+            details.record_disqualification(*entry_idx, &e.author, reason);
+            false
         })
         .collect()
 }
 
 thread_local! {
     // We reuse this buffer for decoding RawValue contents to avoid repeated allocations.
-    // Entries greater than 1000 bytes long (decoded) are disqualified, so we only need 1000 bytes.
-    static DECODE_BUFFER: RefCell<[u8; 1000]> = RefCell::new([0; 1000]);
+    // It keeps its capacity from one entry to the next, and from one contest to the next, so it
+    // ends up as large as the longest contents decoded on this thread, up to the byte limit.
+    static DECODE_BUFFER: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
 }
 
-fn calculate_json_string_length(raw_content: &RawValue) -> Option<usize> {
+pub(crate) fn calculate_json_string_length(
+    raw_content: &RawValue,
+) -> Result<usize, DisqualificationReason> {
     let raw = raw_content.get();
 
     // We expect it to be a quoted JSON string.
     if raw.len() < 2 || !raw.starts_with('"') || !raw.ends_with('"') {
-        return None;
+        return Err(DisqualificationReason::NotAString);
     }
 
     // Remove the quotes.
@@ -231,22 +387,25 @@ fn calculate_json_string_length(raw_content: &RawValue) -> Option<usize> {
     for chunk in unescaped {
         let Ok(chunk) = chunk else {
             // Disqualified due to invalid escape sequence.
-            return None;
+            return Err(DisqualificationReason::InvalidEscape);
         };
 
         len += chunk.len();
     }
 
-    Some(len)
+    Ok(len)
 }
 
-/// Returns None if the entry is disqualified due to length or emptiness.
-fn calculate_weight(raw_content: &RawValue) -> Option<f64> {
+/// Returns the reason for disqualification if the entry is disqualified due to its contents.
+pub(crate) fn calculate_weight(
+    raw_content: &RawValue,
+    judge: &Judge,
+) -> Result<EntryWeight, DisqualificationReason> {
     let raw = raw_content.get();
 
     // We expect it to be a quoted JSON string.
     if raw.len() < 2 || !raw.starts_with('"') || !raw.ends_with('"') {
-        return None;
+        return Err(DisqualificationReason::NotAString);
     }
 
     // Remove the quotes.
@@ -255,39 +414,36 @@ fn calculate_weight(raw_content: &RawValue) -> Option<f64> {
     let unescaped = json_escape::unescape(raw);
 
     DECODE_BUFFER.with_borrow_mut(|buffer| {
-        // How much of the decode buffer is already used.
-        // If we ever overflow the buffer, the entry is disqualified due to length.
-        let mut len = 0;
+        // If the contents ever exceed the byte limit, the entry is disqualified due to length.
+        // Unescaping never makes them longer, so we can size the buffer up front and never
+        // reallocate while decoding.
+        buffer.clear();
+        buffer.reserve(judge.max_bytes.min(raw.len()));
 
         // We first collect (copy) all the bytes into our input buffer.
         for chunk in unescaped {
             let Ok(chunk) = chunk else {
                 // Disqualified due to invalid escape sequence.
-                return None;
+                return Err(DisqualificationReason::InvalidEscape);
             };
 
-            if len + chunk.len() > buffer.len() {
+            if buffer.len() + chunk.len() > judge.max_bytes {
                 // Disqualified due to length.
-                return None;
+                return Err(DisqualificationReason::TooLong);
             }
 
-            buffer[len..len + chunk.len()].copy_from_slice(chunk);
-            len += chunk.len();
+            buffer.extend_from_slice(chunk);
         }
 
-        let Ok(content) = str::from_utf8(&buffer[..len]) else {
+        let Ok(content) = str::from_utf8(buffer) else {
             // Disqualified due to invalid UTF-8.
-            return None;
+            return Err(DisqualificationReason::InvalidUtf8);
         };
 
-        if content.trim().is_empty() {
-            // Disqualified due to emptiness.
-            return None;
-        }
+        // Disqualified due to emptiness or some other rule.
+        judge.check(content)?;
 
-        let length = content.len() as f64;
-        let word_count = content.split_whitespace().count() as f64;
-        Some(length / word_count)
+        Ok(judge.weight(content))
     })
 }
 
@@ -299,6 +455,18 @@ struct Manifest<'json> {
 
     #[serde_as(as = "Vec<BorrowCow>")]
     rounds: Vec<Cow<'json, str>>,
+
+    #[serde(default)]
+    rules: Rules,
+
+    #[serde(default)]
+    weight: Weight,
+
+    #[serde(default)]
+    tie_policy: TiePolicy,
+
+    #[serde(default, deserialize_with = "deserialize_podium")]
+    podium: Option<Vec<NonZeroU64>>,
 }
 
 #[serde_as]
@@ -306,12 +474,25 @@ struct Manifest<'json> {
 struct Category<'json> {
     #[serde_as(as = "Vec<BorrowCow>")]
     keywords: Vec<Cow<'json, str>>,
+
+    points: Option<NonZeroU64>,
+
+    #[serde_as(as = "Option<BorrowCow>")]
+    id: Option<Cow<'json, str>>,
+
+    #[serde_as(as = "Option<BorrowCow>")]
+    name: Option<Cow<'json, str>>,
+
+    #[serde_as(as = "Option<BorrowCow>")]
+    description: Option<Cow<'json, str>>,
 }
 
 #[derive(Deserialize)]
 struct Round<'json> {
     #[serde(borrow)]
     entries: Vec<Entry<'json>>,
+
+    multiplier: Option<NonZeroU64>,
 }
 
 #[derive(Deserialize)]
@@ -345,7 +526,7 @@ mod tests {
         validate_cow_borrowing_round(&round_json);
     }
 
-    fn validate_cow_borrowing_manifest<'a>(manifest_json: &'a str) {
+    fn validate_cow_borrowing_manifest(manifest_json: &str) {
         let manifest: Manifest = serde_json::from_str(manifest_json).unwrap();
 
         let keyword = manifest
@@ -362,7 +543,7 @@ mod tests {
         assert!(matches!(round_path, Cow::Borrowed(_)));
     }
 
-    fn validate_cow_borrowing_round<'a>(round_json: &'a str) {
+    fn validate_cow_borrowing_round(round_json: &str) {
         let round: Round = serde_json::from_str(round_json).unwrap();
 
         let entry = round.entries.first().unwrap();
@@ -370,4 +551,20 @@ mod tests {
         assert!(matches!(entry.author, Cow::Borrowed(_)));
         assert!(matches!(entry.title, Cow::Borrowed(_)));
     }
+
+    #[test]
+    fn validate_cow_borrowing_category_metadata() {
+        let manifest_json = r#"{
+            "categories": [{ "keywords": ["rose"], "id": "roses", "name": "\"Red\" roses" }],
+            "rounds": []
+        }"#;
+
+        let manifest: Manifest = serde_json::from_str(manifest_json).unwrap();
+        let category = manifest.categories.first().unwrap();
+
+        assert!(matches!(category.id, Some(Cow::Borrowed(_))));
+        // Escape sequences must be decoded first, so cannot be borrowed.
+        assert!(matches!(category.name, Some(Cow::Owned(_))));
+        assert!(category.description.is_none());
+    }
 }
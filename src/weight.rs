@@ -0,0 +1,257 @@
+//! Functions that calculate the weight of an entry from its contents. The entry with the highest
+//! weight wins its categories.
+
+use std::{collections::HashSet, fmt::Debug};
+
+use serde::{Deserialize, Serialize};
+
+use crate::WordBoundary;
+
+/// Calculates the weight of an entry from its contents.
+///
+/// Implement this to judge contests by criteria other than the built-in ones and pass it to the
+/// solver via [`SolveOptions::weight_function`][crate::SolveOptions::weight_function].
+pub trait WeightFunction: Debug + Send + Sync {
+    /// The weight of an entry with the given contents, which contain at least one word as
+    /// defined by `word_boundary`. Entries without words are disqualified before they are weighed.
+    ///
+    /// The weight must not be NaN, which cannot be ranked against other weights. Solvers built
+    /// with debug assertions panic on it, others rank such entries in an unspecified way.
+    fn weight(&self, contents: &str, word_boundary: WordBoundary) -> f64;
+}
+
+/// The built-in weight functions, one of which is selected by the `weight` field of the
+/// manifest. Word density is used if the manifest does not select one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Weight {
+    /// See [`WordDensity`].
+    #[default]
+    WordDensity,
+
+    /// See [`CharDensity`].
+    CharDensity,
+
+    /// See [`LineCount`].
+    LineCount,
+
+    /// See [`DistinctWordRatio`].
+    DistinctWordRatio,
+
+    /// See [`SyllableDensity`].
+    SyllableDensity,
+}
+
+impl Weight {
+    /// The function that calculates this kind of weight.
+    pub fn function(self) -> &'static dyn WeightFunction {
+        match self {
+            Weight::WordDensity => &WordDensity,
+            Weight::CharDensity => &CharDensity,
+            Weight::LineCount => &LineCount,
+            Weight::DistinctWordRatio => &DistinctWordRatio,
+            Weight::SyllableDensity => &SyllableDensity,
+        }
+    }
+}
+
+/// Length of the contents in bytes divided by the number of words, as in the original rules.
+#[derive(Debug)]
+pub struct WordDensity;
+
+impl WeightFunction for WordDensity {
+    fn weight(&self, contents: &str, word_boundary: WordBoundary) -> f64 {
+        let length = contents.len() as f64;
+        let word_count = word_boundary.word_count(contents) as f64;
+
+        length / word_count
+    }
+}
+
+/// Length of the contents in characters divided by the number of words. Unlike word density,
+/// this does not favor scripts that need more bytes per character.
+#[derive(Debug)]
+pub struct CharDensity;
+
+impl WeightFunction for CharDensity {
+    fn weight(&self, contents: &str, word_boundary: WordBoundary) -> f64 {
+        let length = contents.chars().count() as f64;
+        let word_count = word_boundary.word_count(contents) as f64;
+
+        length / word_count
+    }
+}
+
+/// Number of lines that contain at least one word.
+#[derive(Debug)]
+pub struct LineCount;
+
+impl WeightFunction for LineCount {
+    fn weight(&self, contents: &str, word_boundary: WordBoundary) -> f64 {
+        contents
+            .lines()
+            .filter(|line| word_boundary.has_words(line))
+            .count() as f64
+    }
+}
+
+/// Number of distinct words divided by the number of words, between 0 (exclusive) and 1. Rewards
+/// a rich vocabulary over repetition. Words are distinct if they differ in any way, including case.
+#[derive(Debug)]
+pub struct DistinctWordRatio;
+
+impl WeightFunction for DistinctWordRatio {
+    fn weight(&self, contents: &str, word_boundary: WordBoundary) -> f64 {
+        let mut distinct_words = HashSet::new();
+        let mut word_count = 0;
+
+        for word in word_boundary.words(contents) {
+            distinct_words.insert(word);
+            word_count += 1;
+        }
+
+        distinct_words.len() as f64 / word_count as f64
+    }
+}
+
+/// Estimated number of syllables divided by the number of words.
+///
+/// Syllables are estimated by counting groups of consecutive vowels in each word, not counting
+/// a silent "e" at the end of an English word. This is only a rough estimate, tuned for English,
+/// but it is cheap and predictable. Every word has at least one syllable, so words in scripts
+/// without (Latin) vowels count as one syllable each.
+#[derive(Debug)]
+pub struct SyllableDensity;
+
+impl WeightFunction for SyllableDensity {
+    fn weight(&self, contents: &str, word_boundary: WordBoundary) -> f64 {
+        let mut syllable_count = 0;
+        let mut word_count = 0;
+
+        for word in word_boundary.words(contents) {
+            syllable_count += estimate_syllables(word);
+            word_count += 1;
+        }
+
+        syllable_count as f64 / word_count as f64
+    }
+}
+
+fn estimate_syllables(word: &str) -> usize {
+    let mut vowel_groups = 0;
+    let mut previous_is_vowel = false;
+
+    for c in word.chars() {
+        let is_vowel = is_vowel(c);
+
+        if is_vowel && !previous_is_vowel {
+            vowel_groups += 1;
+        }
+
+        previous_is_vowel = is_vowel;
+    }
+
+    // The "e" at the end of "stone" is silent, unlike those of "table", "tree" or "the".
+    let letters = word
+        .trim_end_matches(|c: char| !c.is_alphabetic())
+        .to_lowercase();
+    let has_silent_e = letters.ends_with('e')
+        && !letters.ends_with("le")
+        && !letters.ends_with("ee")
+        && vowel_groups > 1;
+
+    if has_silent_e {
+        vowel_groups -= 1;
+    }
+
+    vowel_groups.max(1)
+}
+
+fn is_vowel(c: char) -> bool {
+    // Vowels of the Latin alphabet, with and without diacritics.
+    matches!(
+        c.to_lowercase().next().unwrap_or(c),
+        'a' | 'e'
+            | 'i'
+            | 'o'
+            | 'u'
+            | 'y'
+            | 'à'..='å'
+            | 'æ'
+            | 'è'..='ï'
+            | 'ò'..='ö'
+            | 'ø'..='ü'
+            | 'ý'
+            | 'ÿ'
+            | 'œ'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight(weight: Weight, contents: &str) -> f64 {
+        weight
+            .function()
+            .weight(contents, WordBoundary::UnicodeWhitespace)
+    }
+
+    #[test]
+    fn word_density() {
+        assert_eq!(weight(Weight::WordDensity, "aaaa bb"), 3.5);
+        assert_eq!(weight(Weight::WordDensity, "été"), 5.0);
+    }
+
+    #[test]
+    fn char_density() {
+        assert_eq!(weight(Weight::CharDensity, "aaaa bb"), 3.5);
+        assert_eq!(weight(Weight::CharDensity, "été"), 3.0);
+        assert_eq!(weight(Weight::CharDensity, "古池や 蛙飛び込む"), 4.5);
+    }
+
+    #[test]
+    fn line_count() {
+        assert_eq!(weight(Weight::LineCount, "one"), 1.0);
+        assert_eq!(weight(Weight::LineCount, "one\ntwo\r\n\n  \nthree\n"), 3.0);
+    }
+
+    #[test]
+    fn distinct_word_ratio() {
+        assert_eq!(weight(Weight::DistinctWordRatio, "a b c d"), 1.0);
+        assert_eq!(weight(Weight::DistinctWordRatio, "a b a b"), 0.5);
+        assert_eq!(weight(Weight::DistinctWordRatio, "a A"), 1.0);
+    }
+
+    #[test]
+    fn syllable_estimates() {
+        for (word, syllables) in [
+            ("a", 1),
+            ("the", 1),
+            ("stone", 1),
+            ("table", 2),
+            ("autumn", 2),
+            ("beautiful", 3),
+            ("contest", 2),
+            ("tree", 1),
+            ("Ode,", 1),
+            ("café", 2),
+            ("rhythm", 1),
+            ("蛙", 1),
+        ] {
+            assert_eq!(estimate_syllables(word), syllables, "{word}");
+        }
+    }
+
+    #[test]
+    fn syllable_density() {
+        assert_eq!(weight(Weight::SyllableDensity, "beautiful autumn"), 2.5);
+    }
+
+    #[test]
+    fn weight_is_deserialized_by_name() {
+        let weight: Weight = serde_json::from_str(r#""distinct_word_ratio""#).unwrap();
+        assert_eq!(weight, Weight::DistinctWordRatio);
+        assert!(serde_json::from_str::<Weight>(r#""word density""#).is_err());
+    }
+}
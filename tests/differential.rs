@@ -0,0 +1,109 @@
+//! Runs every solver over generated data sets and checks that they all return the expected result.
+
+use poetry_contest::{
+    SolveOptions,
+    generator::{Generator, Sizes},
+    solvers,
+};
+
+const TINY: &str = include_str!("../examples/presets/tiny.json");
+const EDGE_CASES: &str = include_str!("../examples/presets/edge-cases.json");
+
+/// Data sets to check, as a description and the sizes to generate with.
+fn data_sets() -> Vec<(&'static str, Sizes)> {
+    let preset = |json| serde_json::from_str::<Sizes>(json).unwrap();
+
+    vec![
+        ("tiny", preset(TINY)),
+        ("edge cases", preset(EDGE_CASES)),
+        (
+            "only edge cases",
+            Sizes {
+                edge_case_percent: 100,
+                ..preset(EDGE_CASES)
+            },
+        ),
+        (
+            "few categories, many authors",
+            Sizes {
+                category_count: 2,
+                author_count: 1000,
+                ..preset(EDGE_CASES)
+            },
+        ),
+        (
+            "phrase keywords",
+            Sizes {
+                phrase_keyword_percent: 30,
+                ..preset(EDGE_CASES)
+            },
+        ),
+        (
+            "named categories",
+            Sizes {
+                named_category_percent: 50,
+                ..preset(EDGE_CASES)
+            },
+        ),
+        (
+            "default word counts",
+            Sizes {
+                round_count: 2,
+                min_entries_per_round: 100,
+                max_entries_per_round: 300,
+                vocabulary_size: 1000,
+                edge_case_percent: 10,
+                ..Sizes::default()
+            },
+        ),
+    ]
+}
+
+const SEEDS: [u64; 3] = [1, 2, 3];
+
+#[test]
+fn all_solvers_return_expected_result() {
+    let mut failures = Vec::new();
+
+    for (description, sizes) in data_sets() {
+        for seed in SEEDS {
+            let data_set = Generator::new(seed, sizes.clone()).finish();
+
+            for solver in solvers() {
+                let result = data_set
+                    .solve(*solver, &SolveOptions::default())
+                    .unwrap_or_else(|e| {
+                        panic!(
+                            "{} failed on {description} (seed {seed}): {e}",
+                            solver.name()
+                        )
+                    });
+
+                let differences = data_set.expected.differences(&result);
+
+                if !differences.is_empty() {
+                    failures.push(format!(
+                        "{} on {description} (seed {seed}):\n  {}",
+                        solver.name(),
+                        differences.join("\n  ")
+                    ));
+                }
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn same_seed_generates_same_data_set() {
+    let sizes = serde_json::from_str::<Sizes>(EDGE_CASES).unwrap();
+
+    let a = Generator::new(42, sizes.clone()).finish();
+    let b = Generator::new(42, sizes.clone()).finish();
+    let c = Generator::new(43, sizes).finish();
+
+    assert_eq!(a.manifest_json, b.manifest_json);
+    assert_eq!(a.rounds, b.rounds);
+    assert_ne!(a.rounds, c.rounds);
+}
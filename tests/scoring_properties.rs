@@ -0,0 +1,315 @@
+//! Property tests for the scoring rules documented on `solve()`, checked against every solver
+//! using small contests built in memory.
+
+use std::collections::{BTreeSet, HashMap};
+
+use poetry_contest::{ContestResult, SolveOptions, Solver, solvers};
+use proptest::prelude::*;
+use serde_json::{Value, json};
+
+/// Words that keywords and titles are made of. Few enough that titles often match categories.
+const TITLE_WORDS: [&str; 5] = ["a", "b", "c", "d", "e"];
+
+/// Words that contents are made of. Few enough that entries often tie in weight.
+const CONTENTS_WORDS: [&str; 4] = ["1", "22", "333", "4444"];
+
+/// Longest allowed contents, in bytes.
+const MAX_CONTENTS_LENGTH: usize = 1000;
+
+#[derive(Clone, Debug)]
+struct Contest {
+    /// Keywords of each category.
+    categories: Vec<Vec<&'static str>>,
+
+    /// Entries of each round.
+    rounds: Vec<Vec<Entry>>,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    author: &'static str,
+    title: String,
+    contents: Value,
+}
+
+impl Entry {
+    /// The weight of the entry, `None` if it is disqualified.
+    fn weight(&self) -> Option<f64> {
+        let text = self.contents.as_str()?;
+
+        if text.len() > MAX_CONTENTS_LENGTH || text.trim().is_empty() {
+            return None;
+        }
+
+        Some(text.len() as f64 / text.split_whitespace().count() as f64)
+    }
+
+    fn matches(&self, keywords: &[&str]) -> bool {
+        self.title
+            .split_whitespace()
+            .any(|word| keywords.contains(&word))
+    }
+}
+
+impl Contest {
+    fn solve(&self, solver: &dyn Solver) -> ContestResult {
+        let manifest_json = json!({
+            "categories": self
+                .categories
+                .iter()
+                .map(|keywords| json!({ "keywords": keywords }))
+                .collect::<Vec<_>>(),
+            "rounds": (0..self.rounds.len())
+                .map(|index| format!("round_{index}.json"))
+                .collect::<Vec<_>>(),
+        })
+        .to_string();
+
+        let round_jsons = self
+            .rounds
+            .iter()
+            .enumerate()
+            .map(|(index, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|e| {
+                        json!({
+                            "author": e.author,
+                            "title": e.title,
+                            "contents": e.contents,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                (
+                    format!("round_{index}.json"),
+                    json!({ "entries": entries }).to_string(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let options = SolveOptions {
+            round_details: true,
+            disqualifications: true,
+            ..Default::default()
+        };
+
+        solver
+            .solve_str_with(
+                &manifest_json,
+                &mut |path, round_json| {
+                    round_json.push_str(&round_jsons[path.to_str().unwrap()]);
+                    Ok(())
+                },
+                &options,
+            )
+            .unwrap_or_else(|e| panic!("{} failed: {e}", solver.name()))
+    }
+}
+
+fn text(words: &'static [&'static str], max_words: usize) -> impl Strategy<Value = String> {
+    prop::collection::vec(
+        (
+            prop::sample::select(words),
+            prop::sample::select(&[" ", "  ", "\n", "\t"][..]),
+        ),
+        1..=max_words,
+    )
+    .prop_map(|words| {
+        words
+            .into_iter()
+            .flat_map(|(word, separator)| [word, separator])
+            .collect()
+    })
+}
+
+fn disqualified_contents() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        // Too long.
+        (MAX_CONTENTS_LENGTH + 1..MAX_CONTENTS_LENGTH + 100)
+            .prop_map(|len| Value::from(&"1 ".repeat(len)[..len])),
+        // No non-whitespace contents.
+        prop::collection::vec(prop::sample::select(&[" ", "\n", "\t"][..]), 0..5)
+            .prop_map(|whitespace| Value::from(whitespace.concat())),
+        // Not a string.
+        Just(Value::Null),
+        Just(json!(42)),
+        Just(json!(["1 22 333"])),
+        Just(json!({ "contents": "1 22 333" })),
+    ]
+}
+
+fn entry() -> impl Strategy<Value = Entry> {
+    (
+        prop::sample::select(&["x", "y", "z"][..]),
+        text(&TITLE_WORDS, 3),
+        prop_oneof![
+            4 => text(&CONTENTS_WORDS, 5).prop_map(Value::from),
+            1 => disqualified_contents(),
+        ],
+    )
+        .prop_map(|(author, title, contents)| Entry {
+            author,
+            title,
+            contents,
+        })
+}
+
+fn contest() -> impl Strategy<Value = Contest> {
+    (
+        prop::collection::vec(
+            prop::collection::vec(prop::sample::select(&TITLE_WORDS[..]), 1..3),
+            1..4,
+        ),
+        prop::collection::vec(prop::collection::vec(entry(), 0..8), 1..4),
+    )
+        .prop_map(|(categories, rounds)| Contest { categories, rounds })
+}
+
+/// A contest and the same contest with the entries of each round in a different order.
+fn contest_and_shuffled() -> impl Strategy<Value = (Contest, Contest)> {
+    contest().prop_flat_map(|contest| {
+        let shuffled_rounds = contest
+            .rounds
+            .iter()
+            .map(|entries| Just(entries.clone()).prop_shuffle())
+            .collect::<Vec<_>>();
+
+        (Just(contest), shuffled_rounds).prop_map(|(contest, rounds)| {
+            let shuffled = Contest {
+                categories: contest.categories.clone(),
+                rounds,
+            };
+
+            (contest, shuffled)
+        })
+    })
+}
+
+proptest! {
+    #[test]
+    fn author_gets_at_most_one_point_per_category_per_round(contest in contest()) {
+        for solver in solvers() {
+            let result = contest.solve(*solver);
+            let mut points = 0;
+
+            for round in result.rounds().unwrap() {
+                for winners in &round.categories {
+                    let unique_authors = winners.authors.iter().collect::<BTreeSet<_>>();
+                    prop_assert_eq!(
+                        unique_authors.len(),
+                        winners.authors.len(),
+                        "{}",
+                        solver.name()
+                    );
+
+                    points += winners.authors.len() as u64;
+                }
+            }
+
+            // Every point is accounted for by a category winner.
+            prop_assert_eq!(points, result.total(), "{}", solver.name());
+        }
+    }
+
+    #[test]
+    fn round_points_at_least_matched_categories(contest in contest()) {
+        for solver in solvers() {
+            let result = contest.solve(*solver);
+
+            for (round, entries) in result.rounds().unwrap().iter().zip(&contest.rounds) {
+                // Categories matched by at least one entry that is not disqualified.
+                let matched = contest
+                    .categories
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, keywords)| {
+                        entries.iter().any(|e| e.weight().is_some() && e.matches(keywords))
+                    })
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+
+                let won = round.categories.iter().map(|w| w.category).collect::<Vec<_>>();
+                prop_assert_eq!(&won, &matched, "{}", solver.name());
+
+                let points = round
+                    .categories
+                    .iter()
+                    .map(|w| w.authors.len())
+                    .sum::<usize>();
+                prop_assert!(points >= matched.len(), "{}", solver.name());
+            }
+        }
+    }
+
+    #[test]
+    fn winners_have_highest_word_density(contest in contest()) {
+        for solver in solvers() {
+            let result = contest.solve(*solver);
+
+            for (round, entries) in result.rounds().unwrap().iter().zip(&contest.rounds) {
+                for winners in &round.categories {
+                    let keywords = &contest.categories[winners.category];
+
+                    let highest = entries
+                        .iter()
+                        .filter(|e| e.matches(keywords))
+                        .filter_map(Entry::weight)
+                        .fold(f64::MIN, f64::max);
+                    prop_assert_eq!(winners.weight, highest, "{}", solver.name());
+
+                    // Every author with an entry of the highest weight wins, nobody else does.
+                    let expected_authors = entries
+                        .iter()
+                        .filter(|e| e.matches(keywords) && e.weight() == Some(highest))
+                        .map(|e| e.author)
+                        .collect::<BTreeSet<_>>();
+                    let authors = winners
+                        .authors
+                        .iter()
+                        .map(String::as_str)
+                        .collect::<BTreeSet<_>>();
+                    prop_assert_eq!(authors, expected_authors, "{}", solver.name());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn adding_disqualified_entry_never_changes_scores(
+        contest in contest(),
+        disqualified in (entry(), disqualified_contents()),
+        round in any::<prop::sample::Index>(),
+        position in any::<prop::sample::Index>(),
+    ) {
+        let (mut entry, contents) = disqualified;
+        entry.contents = contents;
+
+        let mut with_disqualified = contest.clone();
+        let entries = &mut with_disqualified.rounds[round.index(contest.rounds.len())];
+        entries.insert(position.index(entries.len() + 1), entry);
+
+        for solver in solvers() {
+            let before = contest.solve(*solver);
+            let after = with_disqualified.solve(*solver);
+
+            prop_assert_eq!(before.scores(), after.scores(), "{}", solver.name());
+            prop_assert_eq!(
+                after.disqualifications().unwrap().len(),
+                before.disqualifications().unwrap().len() + 1,
+                "{}",
+                solver.name()
+            );
+        }
+    }
+
+    #[test]
+    fn entry_order_does_not_change_scores((contest, shuffled) in contest_and_shuffled()) {
+        for solver in solvers() {
+            let before = contest.solve(*solver);
+            let after = shuffled.solve(*solver);
+
+            prop_assert_eq!(before.scores(), after.scores(), "{}", solver.name());
+        }
+    }
+}